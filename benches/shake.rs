@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+use bevy_camera_shake::{testing, NoiseKind, Shake2d, ShakeNoise, ShakeSource, TraumaDecay};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const ENTITIES: u32 = 10_000;
+
+/// Spawns `ENTITIES` props with a `Shake2d`, each with its own seed.
+fn app_with_shakes(trauma: f32, noise: bool) -> App {
+    let mut app = testing::app();
+    for seed in 0..ENTITIES {
+        let shake = Shake2d {
+            max_offset: Vec2::new(4.0, 4.0),
+            trauma,
+            decay: TraumaDecay::Linear(0.0),
+            random_sources: [0, 1, 2].map(|axis| ShakeSource::perlin(seed * 3 + axis)),
+            ..default()
+        };
+        let mut entity = app.world_mut().spawn((Transform::default(), shake));
+        if noise {
+            entity.insert(ShakeNoise::new(NoiseKind::Perlin, seed));
+        }
+    }
+    // Settles the change detection of the newly spawned entities.
+    app.update();
+    app
+}
+
+fn shake_10k(c: &mut Criterion) {
+    let mut group = c.benchmark_group("10k Shake2d");
+    for (name, trauma, noise) in [
+        ("random sources", 1.0, false),
+        ("shake noise", 1.0, true),
+        ("at rest", 0.0, true),
+    ] {
+        let mut app = app_with_shakes(trauma, noise);
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| app.update())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, shake_10k);
+criterion_main!(benches);
@@ -1,23 +1,18 @@
 use bevy::prelude::*;
-use bevy_camera_shake::{CameraShakePlugin, RandomSource, Shake2d};
+use bevy_camera_shake::{
+    AddTrauma, CameraShakePlugin, Shake2d, ShakeMode, ShakeSource, TraumaDecay,
+};
 use rand::{thread_rng, Rng};
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_plugins(CameraShakePlugin)
+        .add_plugins(CameraShakePlugin::default())
         .add_systems(Startup, setup)
         .add_systems(Update, player_movement)
         .add_systems(Update, add_shake)
         .run();
 }
-struct MyRandom;
-
-impl RandomSource for MyRandom {
-    fn rand(&self, _time: f32) -> f32 {
-        random_number()
-    }
-}
 
 fn random_number() -> f32 {
     let mut rng = thread_rng();
@@ -39,8 +34,13 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
             max_roll: 0.2,
             trauma: 0.0,
             trauma_power: 2.0,
-            decay: 0.8,
-            random_sources: [Box::new(MyRandom), Box::new(MyRandom), Box::new(MyRandom)],
+            decay: TraumaDecay::Linear(0.8),
+            random_sources: [
+                ShakeSource::custom(|_: f32| random_number()),
+                ShakeSource::custom(|_: f32| random_number()),
+                ShakeSource::custom(|_: f32| random_number()),
+            ],
+            mode: ShakeMode::Replace,
         })
         .insert(Transform::default())
         .id();
@@ -109,17 +109,15 @@ fn player_movement(
 const TRAUMA_AMOUNT: f32 = 0.5;
 
 // Adds trauma to all Shake2d entities when the `r` key is pressed.
-// Limits trauma to a maximum of `1.0`.
-fn add_shake(mut shakeables: Query<&mut Shake2d>, keyboard_input: Res<ButtonInput<KeyCode>>) {
+// Trauma is limited to a maximum of `1.0` by the plugin.
+fn add_shake(
+    shakeables: Query<Entity, With<Shake2d>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut trauma_events: EventWriter<AddTrauma>,
+) {
     if keyboard_input.just_pressed(KeyCode::KeyR) {
-        for mut shakeable in shakeables.iter_mut() {
-            let past_trauma = shakeable.trauma;
-            let current_trauma = f32::min(shakeable.trauma + TRAUMA_AMOUNT, 1.0);
-            info!(
-                "Past trauma: {}, Current trauma: {}",
-                past_trauma, current_trauma
-            );
-            shakeable.trauma = current_trauma;
+        for shakeable in shakeables.iter() {
+            trauma_events.write(AddTrauma::new(shakeable, TRAUMA_AMOUNT));
         }
     }
 }
@@ -1,40 +1,22 @@
 use bevy::prelude::*;
-use bevy_camera_shake::{CameraShakePlugin, RandomSource, Shake2d};
-use noise::{NoiseFn, OpenSimplex};
+use bevy_camera_shake::{
+    AddTrauma, CameraShakePlugin, CameraShakeSettings, ProjectionShake, Shake2d, ShakeMode,
+    ShakeSource, TraumaDecay,
+};
 use rand::{thread_rng, Rng};
 
 // In this example, we use Simplex noise instead of random noise, to get a "smoother" shake.
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_plugins(CameraShakePlugin)
+        .add_plugins(CameraShakePlugin::default())
         .add_systems(Startup, setup)
         .add_systems(Update, player_movement)
         .add_systems(Update, add_shake)
+        .add_systems(Update, toggle_reduced_motion)
         .run();
 }
 
-// Struct used ot hold our simplex noise.
-// We have to use a seperate struct (rather than implementing `RandomSource` on `OpenSimplex` directly)
-// due to https://doc.rust-lang.org/error_codes/E0117.html
-struct MyNoise {
-    pub simplex: OpenSimplex,
-}
-
-impl MyNoise {
-    pub fn new(seed: u32) -> MyNoise {
-        MyNoise {
-            simplex: OpenSimplex::new(seed),
-        }
-    }
-}
-
-impl RandomSource for MyNoise {
-    fn rand(&self, time: f32) -> f32 {
-        self.simplex.get([(time * 15.0) as f64, 0.0]) as f32
-    }
-}
-
 fn random_number() -> f32 {
     let mut rng = thread_rng();
     let x: f32 = rng.gen();
@@ -46,8 +28,10 @@ fn random_number() -> f32 {
 struct Player;
 
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
-    // Create a default camera.
-    let camera_id = commands.spawn(Camera2d).id();
+    // Create a default camera, which zooms out slightly while the Shake2d entity above it shakes.
+    let camera_id = commands
+        .spawn((Camera2d, ProjectionShake::punch(0.0, 0.1)))
+        .id();
     // Create a Shake2d entity.
     // We set decay to a lower value of 0.7. That means after 1 second, only `1.0 - 0.7` or 30% of the trauma will remain.
     // After two seconds, we will have no trauma left because `(1.0 - (2.0 * 0.7)) < 0.0`.
@@ -57,12 +41,14 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
             max_roll: 0.1,
             trauma: 0.0,
             trauma_power: 2.0,
-            decay: 0.7,
+            decay: TraumaDecay::Linear(0.7),
+            // Each axis gets its own seed, so the axes don't move in lockstep.
             random_sources: [
-                Box::new(MyNoise::new(0)),
-                Box::new(MyNoise::new(1)),
-                Box::new(MyNoise::new(2)),
+                ShakeSource::open_simplex(0),
+                ShakeSource::open_simplex(1),
+                ShakeSource::open_simplex(2),
             ],
+            mode: ShakeMode::Replace,
         })
         .insert(Transform::default())
         .id();
@@ -98,6 +84,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     // Make the Shake2d entity the parent of the camera (so the camera moves when the Shake2d component shakes).
     commands.entity(shake_id).add_children(&[camera_id]);
     println!("Press R to add trauma to the camera.");
+    println!("Press M to toggle reduced motion.");
 }
 
 // Move up/down/left/right based on player's input.
@@ -131,17 +118,28 @@ fn player_movement(
 const TRAUMA_AMOUNT: f32 = 0.5;
 
 // Adds trauma to all Shake2d entities when the `r` key is pressed.
-// Limits trauma to a maximum of `1.0`.
-fn add_shake(mut shakeables: Query<&mut Shake2d>, keyboard_input: Res<ButtonInput<KeyCode>>) {
+// Trauma is limited to a maximum of `1.0` by the plugin.
+fn add_shake(
+    shakeables: Query<Entity, With<Shake2d>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut trauma_events: EventWriter<AddTrauma>,
+) {
     if keyboard_input.just_pressed(KeyCode::KeyR) {
-        for mut shakeable in shakeables.iter_mut() {
-            let past_trauma = shakeable.trauma;
-            let current_trauma = f32::min(shakeable.trauma + TRAUMA_AMOUNT, 1.0);
-            info!(
-                "Past trauma: {}, Current trauma: {}",
-                past_trauma, current_trauma
-            );
-            shakeable.trauma = current_trauma;
+        for shakeable in shakeables.iter() {
+            trauma_events.write(AddTrauma::new(shakeable, TRAUMA_AMOUNT));
         }
     }
 }
+
+// Toggles the reduced motion accessibility setting when the `m` key is pressed.
+// With reduced motion enabled, the camera doesn't roll, and only moves at a quarter of the distance.
+fn toggle_reduced_motion(
+    mut settings: ResMut<CameraShakeSettings>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyM) {
+        settings.reduced_motion = !settings.reduced_motion;
+        settings.reduced_motion_intensity = 0.25;
+        info!("Reduced motion: {}", settings.reduced_motion);
+    }
+}
@@ -1,15 +1,18 @@
 use bevy::prelude::*;
 use bevy::window::{CursorGrabMode, PrimaryWindow};
-use bevy_camera_shake::{CameraShakePlugin, RandomSource, Shake3d};
+use bevy_camera_shake::{
+    CameraShakePlugin, Shake3d, ShakeCommandsExt, ShakeKick, ShakeMode, ShakeSource, TraumaDecay,
+};
 use rand::{thread_rng, Rng};
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_plugins(CameraShakePlugin)
+        .add_plugins(CameraShakePlugin::default())
         .add_plugins(NoCameraPlayerPlugin)
         .add_systems(Startup, setup)
         .add_systems(Update, add_shake)
+        .add_systems(Update, fire)
         .run();
 }
 
@@ -18,13 +21,6 @@ fn random_number() -> f32 {
     let x: f32 = rng.gen();
     x * 2.0 - 1.0
 }
-struct MyRandom;
-
-impl RandomSource for MyRandom {
-    fn rand(&self, _time: f32) -> f32 {
-        random_number()
-    }
-}
 
 #[derive(Component)]
 struct Player {
@@ -49,35 +45,31 @@ fn setup(
         },
         Transform::from_xyz(4.0, 8.0, 4.0),
     ));
-    let camera_id = commands
-        .spawn((
-            Camera3d::default(),
-            Transform::from_xyz(0.0, 0.0, 0.0).looking_at(Vec3::NEG_Z, Vec3::Y),
-        ))
-        .id();
-    let shake_id = commands
-        .spawn(Shake3d {
+    // The shake is put directly on the camera, which is also moved by the flycam controller.
+    // `ShakeMode::Additive` layers the shake on top of the controller's movement instead of overwriting it.
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 0.0).looking_at(Vec3::NEG_Z, Vec3::Y),
+        Player { speed: 5.0 },
+        Shake3d {
             max_offset: Vec3::new(0.0, 0.0, 0.0),
             max_yaw_pitch_roll: Vec3::new(0.1, 0.1, 0.1),
             trauma: 0.0,
             trauma_power: 2.0,
-            decay: 0.8,
+            decay: TraumaDecay::Linear(0.8),
             random_sources: [
-                Box::new(MyRandom),
-                Box::new(MyRandom),
-                Box::new(MyRandom),
-                Box::new(MyRandom),
-                Box::new(MyRandom),
-                Box::new(MyRandom),
+                ShakeSource::custom(|_: f32| random_number()),
+                ShakeSource::custom(|_: f32| random_number()),
+                ShakeSource::custom(|_: f32| random_number()),
+                ShakeSource::custom(|_: f32| random_number()),
+                ShakeSource::custom(|_: f32| random_number()),
+                ShakeSource::custom(|_: f32| random_number()),
             ],
-        })
-        .insert(Transform::default())
-        .id();
-
-    let player_id = commands
-        .spawn(Player { speed: 5.0 })
-        .insert(Transform::default())
-        .id();
+            mode: ShakeMode::Additive,
+        },
+        // A spring that kicks the camera upwards when firing, then settles back.
+        ShakeKick::default(),
+    ));
 
     for _ in 0..250 {
         commands.spawn((
@@ -94,23 +86,32 @@ fn setup(
             },
         ));
     }
-    commands.entity(player_id).add_children(&[shake_id]);
-    commands.entity(shake_id).add_children(&[camera_id]);
     println!("Press R to add trauma to the camera.");
+    println!("Click to fire, which kicks the camera upwards.");
 }
 
 const TRAUMA_AMOUNT: f32 = 0.5;
 
-fn add_shake(mut shakeables: Query<&mut Shake3d>, keyboard_input: Res<ButtonInput<KeyCode>>) {
+fn add_shake(
+    mut commands: Commands,
+    shakeables: Query<Entity, With<Shake3d>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
     if keyboard_input.just_pressed(KeyCode::KeyR) {
-        for mut shakeable in shakeables.iter_mut() {
-            let past_trauma = shakeable.trauma;
-            let current_trauma = f32::min(shakeable.trauma + TRAUMA_AMOUNT, 1.0);
-            info!(
-                "Past trauma: {}, Current trauma: {}",
-                past_trauma, current_trauma
+        for shakeable in shakeables.iter() {
+            commands.entity(shakeable).add_trauma(TRAUMA_AMOUNT);
+        }
+    }
+}
+
+// Kicks the camera upwards, with a little random yaw, like the recoil of a weapon.
+fn fire(mut kicks: Query<&mut ShakeKick>, mouse_input: Res<ButtonInput<MouseButton>>) {
+    if mouse_input.just_pressed(MouseButton::Left) {
+        for mut kick in kicks.iter_mut() {
+            kick.kick(
+                Vec3::new(0.0, 0.0, 0.5),
+                Vec3::new(random_number() * 0.3, 2.0, 0.0),
             );
-            shakeable.trauma = current_trauma;
         }
     }
 }
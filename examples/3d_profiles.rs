@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use bevy_camera_shake::{
+    CameraShakePlugin, Shake3d, ShakeCommandsExt, ShakeMode, ShakeProfile, ShakeProfileHandle,
+    ShakeProfilePlugin,
+};
+
+// In this example, the shake parameters are loaded from `.shake.ron` files in `assets/shakes`.
+// Run with `--features bevy/file_watcher` to see changes to the files applied while the example is running.
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(CameraShakePlugin::default())
+        .add_plugins(ShakeProfilePlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, play_profile)
+        .run();
+}
+
+// The profiles that can be played, and the keys that play them.
+const PROFILES: [(KeyCode, &str); 4] = [
+    (KeyCode::Digit1, "shakes/explosion_small.shake.ron"),
+    (KeyCode::Digit2, "shakes/earthquake.shake.ron"),
+    (KeyCode::Digit3, "shakes/footstep.shake.ron"),
+    (KeyCode::Digit4, "shakes/helicopter.shake.ron"),
+];
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn((
+        Mesh3d(meshes.add(Mesh::from(Plane3d::new(Vec3::Y, Vec2::splat(150.0))))),
+        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.5, 0.3))),
+        Transform::from_xyz(0.0, -0.5, 0.0),
+    ));
+    commands.spawn((
+        PointLight {
+            intensity: 1500.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(4.0, 8.0, 4.0),
+    ));
+    commands.spawn((
+        Mesh3d(meshes.add(Mesh::from(Cuboid::new(1.0, 1.0, 1.0)))),
+        MeshMaterial3d(materials.add(Color::srgb(1.0, 0.8, 0.6))),
+        Transform::default(),
+    ));
+    // The parameters of the shake are replaced with the profile's once it is loaded.
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 2.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+        Shake3d {
+            mode: ShakeMode::Additive,
+            ..default()
+        },
+        ShakeProfileHandle(asset_server.load(PROFILES[0].1)),
+    ));
+    println!("Press 1, 2, 3 or 4 to play the small explosion, earthquake, footstep or helicopter profile.");
+}
+
+fn play_profile(
+    mut commands: Commands,
+    mut shakeables: Query<(Entity, &mut ShakeProfileHandle), With<Shake3d>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (key, path) in PROFILES {
+        if keyboard_input.just_pressed(key) {
+            let profile: Handle<ShakeProfile> = asset_server.load(path);
+            for (shakeable, mut handle) in shakeables.iter_mut() {
+                handle.0 = profile.clone();
+                commands.entity(shakeable).set_trauma_at_least(1.0);
+            }
+        }
+    }
+}
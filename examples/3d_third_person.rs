@@ -1,15 +1,19 @@
 use bevy::prelude::*;
 use bevy::window::{CursorGrabMode, PrimaryWindow};
-use bevy_camera_shake::{CameraShakePlugin, RandomSource, Shake3d};
+use bevy_camera_shake::{
+    CameraShakePlugin, Falloff, Shake3d, ShakeCommandsExt, ShakeDirection, ShakeImpulse, ShakeMode,
+    ShakeSource, TraumaDecay,
+};
 use rand::{thread_rng, Rng};
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_plugins(CameraShakePlugin)
+        .add_plugins(CameraShakePlugin::default())
         .add_plugins(NoCameraPlayerPlugin)
         .add_systems(Startup, setup)
         .add_systems(Update, add_shake)
+        .add_systems(Update, explode)
         .run();
 }
 
@@ -18,13 +22,6 @@ fn random_number() -> f32 {
     let x: f32 = rng.gen();
     x * 2.0 - 1.0
 }
-struct MyRandom;
-
-impl RandomSource for MyRandom {
-    fn rand(&self, _time: f32) -> f32 {
-        random_number()
-    }
-}
 
 #[derive(Component)]
 struct Player {
@@ -65,16 +62,19 @@ fn setup(
             max_yaw_pitch_roll: Vec3::new(0.1, 0.1, 0.1),
             trauma: 0.0,
             trauma_power: 2.0,
-            decay: 0.8,
+            decay: TraumaDecay::Linear(0.8),
             random_sources: [
-                Box::new(MyRandom),
-                Box::new(MyRandom),
-                Box::new(MyRandom),
-                Box::new(MyRandom),
-                Box::new(MyRandom),
-                Box::new(MyRandom),
+                ShakeSource::custom(|_: f32| random_number()),
+                ShakeSource::custom(|_: f32| random_number()),
+                ShakeSource::custom(|_: f32| random_number()),
+                ShakeSource::custom(|_: f32| random_number()),
+                ShakeSource::custom(|_: f32| random_number()),
+                ShakeSource::custom(|_: f32| random_number()),
             ],
+            mode: ShakeMode::Replace,
         })
+        // Orients the shake away from explosions.
+        .insert(ShakeDirection::default())
         .insert(Transform::default())
         .id();
 
@@ -116,24 +116,41 @@ fn setup(
     commands.entity(player_id).add_children(&[player_face_id]);
     commands.entity(shake_id).add_children(&[camera_id]);
     println!("Press R to add trauma to the camera.");
+    println!("Press E to set off an explosion somewhere near the player.");
 }
 
 const TRAUMA_AMOUNT: f32 = 0.5;
 
-fn add_shake(mut shakeables: Query<&mut Shake3d>, keyboard_input: Res<ButtonInput<KeyCode>>) {
+fn add_shake(
+    mut commands: Commands,
+    shakeables: Query<Entity, With<Shake3d>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
     if keyboard_input.just_pressed(KeyCode::KeyR) {
-        for mut shakeable in shakeables.iter_mut() {
-            let past_trauma = shakeable.trauma;
-            let current_trauma = f32::min(shakeable.trauma + TRAUMA_AMOUNT, 1.0);
-            info!(
-                "Past trauma: {}, Current trauma: {}",
-                past_trauma, current_trauma
-            );
-            shakeable.trauma = current_trauma;
+        for shakeable in shakeables.iter() {
+            commands.entity(shakeable).add_trauma(TRAUMA_AMOUNT);
         }
     }
 }
 
+// Sets off an explosion at a random position when the `e` key is pressed.
+// The closer the explosion is to the camera, the more trauma it adds.
+fn explode(
+    mut impulses: EventWriter<ShakeImpulse>,
+    player: Single<&Transform, With<Player>>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyE) {
+        let position =
+            player.translation + Vec3::new(random_number() * 20.0, 0.0, random_number() * 20.0);
+        info!("Explosion at {}", position);
+        impulses.write(
+            ShakeImpulse::new(position, 1.0, 25.0)
+                .with_falloff(Falloff::InverseSquare { min_distance: 3.0 }),
+        );
+    }
+}
+
 // Code shamelessly stolen and edited from https://github.com/sburris0/bevy_flycam/blob/master/src/lib.rs
 
 use bevy::ecs::event::{EventCursor, Events};
@@ -0,0 +1,148 @@
+//! Deterministic shakes, for replays and rollback netcode.
+
+use bevy::prelude::{
+    Component, EntityRef, EntityWorldMut, Reflect, ReflectComponent, ReflectDefault,
+};
+#[cfg(feature = "serde")]
+use bevy::prelude::{ReflectDeserialize, ReflectSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{AppliedShake, Shake2d, Shake3d, ShakeDirection, ShakeKick, ShakeLayers, TimedShake};
+
+/// Makes a `Shake2d` or `Shake3d` deterministic, by sampling its random sources with its own seed and clock
+/// instead of the app's elapsed time.
+///
+/// The clock advances by the schedule's delta time every time the shake is applied, even while the shake is at rest,
+/// so the shake only depends on the trauma added to it and on the number of times it ran.
+/// For identical offsets across replays or rollback resimulation, apply the shake in a fixed schedule (for example `CameraShakePlugin::new(FixedUpdate)`),
+/// and use random sources that only depend on the time, such as the built-in noise.
+///
+/// Shakes with different seeds sample different parts of their random sources, so they don't move in lockstep.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ShakeClock {
+    /// The seed of the shake.
+    /// Defaults to `0`.
+    pub seed: u32,
+    /// The number of seconds the shake has been applied for.
+    /// Defaults to `0.0`.
+    pub elapsed: f32,
+}
+
+impl ShakeClock {
+    /// Creates a `ShakeClock` with the given seed, starting at zero.
+    pub fn new(seed: u32) -> Self {
+        Self { seed, elapsed: 0.0 }
+    }
+
+    /// The time the random sources are sampled at.
+    /// The seed offsets the clock by up to 1024 seconds, which is far enough apart for the noise to be unrelated
+    /// while keeping enough precision in an `f32`.
+    pub fn sample_time(&self) -> f32 {
+        let offset = self.seed.wrapping_mul(0x9E37_79B9) >> 16;
+        offset as f32 / 64.0 + self.elapsed
+    }
+}
+
+/// The state of a shake that changes over time: its trauma, the trauma of its layers, its clock,
+/// its kick, its direction, its envelope, and the shake that was applied to its `Transform` this frame.
+///
+/// The shake applied by a `ProjectionShake` and the state held by `ShakeSource::Sampler`s are not captured,
+/// so shakes using stateful samplers are not deterministic across a rollback.
+///
+/// Capture it when saving a rollback frame, and restore it when rolling back,
+/// so that resimulating the same inputs produces the same camera offsets.
+///
+/// ```ignore
+/// let snapshot = ShakeSnapshot::capture(world.entity(camera)).unwrap();
+/// // Later:
+/// snapshot.restore(&mut world.entity_mut(camera));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ShakeSnapshot {
+    /// The trauma of the `Shake2d` or `Shake3d`.
+    pub trauma: f32,
+    /// The trauma of every layer of the `ShakeLayers`, in order, if the shake has one.
+    pub layer_trauma: Option<Vec<f32>>,
+    /// The `ShakeClock`, if the shake has one.
+    pub clock: Option<ShakeClock>,
+    /// The `ShakeKick`, if the shake has one.
+    pub kick: Option<ShakeKick>,
+    /// The `ShakeDirection`, if the shake has one.
+    pub direction: Option<ShakeDirection>,
+    /// The `TimedShake`, if the shake has one.
+    pub timed: Option<TimedShake>,
+    /// The shake that was applied to the entity.
+    pub applied: AppliedShake,
+}
+
+impl ShakeSnapshot {
+    /// Captures the state of the shake on an entity.
+    /// Returns `None` if the entity has no `Shake2d` or `Shake3d`.
+    pub fn capture<'w>(entity: impl Into<EntityRef<'w>>) -> Option<Self> {
+        let entity = entity.into();
+        let trauma = match (entity.get::<Shake2d>(), entity.get::<Shake3d>()) {
+            (Some(shake), _) => shake.trauma,
+            (None, Some(shake)) => shake.trauma,
+            (None, None) => return None,
+        };
+        Some(Self {
+            trauma,
+            layer_trauma: entity
+                .get::<ShakeLayers>()
+                .map(|layers| layers.layers.iter().map(|layer| layer.trauma).collect()),
+            clock: entity.get::<ShakeClock>().copied(),
+            kick: entity.get::<ShakeKick>().copied(),
+            direction: entity.get::<ShakeDirection>().copied(),
+            timed: entity.get::<TimedShake>().copied(),
+            applied: entity.get::<AppliedShake>().copied().unwrap_or_default(),
+        })
+    }
+
+    /// Restores the state of the shake on an entity.
+    /// Captured components that the entity no longer has, such as a finished `TimedShake`, are inserted again,
+    /// and the `ShakeClock`, `ShakeKick`, `ShakeDirection`, `TimedShake` or `ShakeLayers` it didn't have when captured
+    /// are removed.
+    /// `ShakeLayers` only capture the trauma of their layers, so removed layers can't be inserted again.
+    pub fn restore(&self, entity: &mut EntityWorldMut) {
+        if let Some(mut shake) = entity.get_mut::<Shake2d>() {
+            shake.trauma = self.trauma;
+        } else if let Some(mut shake) = entity.get_mut::<Shake3d>() {
+            shake.trauma = self.trauma;
+        }
+        if let Some(layer_trauma) = &self.layer_trauma {
+            if let Some(mut layers) = entity.get_mut::<ShakeLayers>() {
+                for (layer, trauma) in layers.layers.iter_mut().zip(layer_trauma) {
+                    layer.trauma = *trauma;
+                }
+            }
+        } else {
+            entity.remove::<ShakeLayers>();
+        }
+        restore_component(entity, self.clock);
+        restore_component(entity, self.kick);
+        restore_component(entity, self.direction);
+        restore_component(entity, self.timed);
+        entity.insert(self.applied);
+    }
+}
+
+/// Inserts the captured component, or removes it if the entity didn't have it when captured.
+fn restore_component<C: Component>(entity: &mut EntityWorldMut, snapshot: Option<C>) {
+    match snapshot {
+        Some(component) => {
+            entity.insert(component);
+        }
+        None => {
+            entity.remove::<C>();
+        }
+    }
+}
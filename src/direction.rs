@@ -0,0 +1,86 @@
+//! Shakes oriented by the direction of the impact that caused them.
+
+use bevy::prelude::{Component, Quat, Reflect, ReflectComponent, ReflectDefault, Vec3};
+#[cfg(feature = "serde")]
+use bevy::prelude::{ReflectDeserialize, ReflectSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Orients the shake of a `Shake2d` or `Shake3d` by the direction of the impacts that add trauma to it,
+/// so a hit from the left looks different from a hit from behind.
+///
+/// Directions come from `AddTrauma::with_direction`, and from `ShakeImpulse`s (pointing from the impulse to the shake).
+/// The translation is biased along the direction, and the rotation is biased away from rotating around it,
+/// so the camera tips away from the impact. The direction is resolved against the unshaken orientation of the
+/// entity, from its `Transform` and its parent's `GlobalTransform`, and is cleared when the trauma reaches zero.
+/// Rotations of the parent in the same frame only reach the shake on the next frame.
+/// For a `Shake2d`, only the X and Y of the direction affect the shake, and the depth of impulses is ignored.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ShakeDirection {
+    /// How strongly the shake is biased along the direction, between `0.0` and `1.0`.
+    /// At `0.0` the direction has no effect, at `1.0` the shake only moves along the direction.
+    /// Defaults to `0.75`.
+    pub anisotropy: f32,
+    /// The current direction of the shake in world space, weighted by the trauma of every impact.
+    /// `Vec3::ZERO` when there is no direction.
+    pub direction: Vec3,
+}
+
+impl Default for ShakeDirection {
+    fn default() -> Self {
+        Self {
+            anisotropy: 0.75,
+            direction: Vec3::ZERO,
+        }
+    }
+}
+
+impl ShakeDirection {
+    /// Creates a `ShakeDirection` with the given anisotropy and no direction.
+    pub fn new(anisotropy: f32) -> Self {
+        Self {
+            anisotropy,
+            direction: Vec3::ZERO,
+        }
+    }
+
+    /// Blends the direction of an impact into the current direction,
+    /// weighted by the `amount` of trauma it adds and the `trauma` the shake had before it.
+    pub(crate) fn add(&mut self, direction: Vec3, amount: f32, trauma: f32) {
+        self.direction =
+            (self.direction * trauma + direction.normalize_or_zero() * amount).normalize_or_zero();
+    }
+
+    /// Biases the translation and yaw, pitch and roll of a shake with the direction,
+    /// given the unshaken world space rotation of the shake entity.
+    pub(crate) fn bias(
+        &self,
+        rotation: Quat,
+        translation: Vec3,
+        yaw_pitch_roll: Vec3,
+    ) -> (Vec3, Vec3) {
+        if self.direction == Vec3::ZERO {
+            return (translation, yaw_pitch_roll);
+        }
+        let keep = 1.0 - self.anisotropy.clamp(0.0, 1.0);
+        let local_direction = rotation.inverse() * self.direction;
+
+        // Keeps the translation along the direction, and reduces the rest.
+        let along = local_direction * translation.dot(local_direction);
+        let translation = along + (translation - along) * keep;
+
+        // As a rotation vector around X, Y and Z: reduces the rotation around the direction, and keeps the rest.
+        let rotation_vector = Vec3::new(yaw_pitch_roll.y, yaw_pitch_roll.x, yaw_pitch_roll.z);
+        let around = local_direction * rotation_vector.dot(local_direction);
+        let rotation_vector = around * keep + (rotation_vector - around);
+        let yaw_pitch_roll = Vec3::new(rotation_vector.y, rotation_vector.x, rotation_vector.z);
+
+        (translation, yaw_pitch_roll)
+    }
+}
@@ -0,0 +1,223 @@
+//! Shake that originates from a position in the world, and gets weaker with distance.
+
+use bevy::prelude::{
+    Component, DetectChangesMut, Event, EventReader, GlobalTransform, Mut, Query, Transform,
+    Trigger, Vec3, Without,
+};
+
+use crate::{
+    shake::ShakeCore,
+    trauma::{add_trauma_to_shake, TraumaMode},
+    Shake2d, Shake3d, ShakeDirection,
+};
+
+/// The components used to add positional trauma to a shake.
+type PositionalTarget<S> = (
+    &'static mut S,
+    &'static GlobalTransform,
+    Option<&'static mut ShakeDirection>,
+);
+/// The 2D shakes positional trauma is added to.
+type Shakes2d<'w, 's> = Query<'w, 's, PositionalTarget<Shake2d>>;
+/// The 3D shakes positional trauma is added to, excluding entities that also have a `Shake2d`.
+type Shakes3d<'w, 's> = Query<'w, 's, PositionalTarget<Shake3d>, Without<Shake2d>>;
+
+/// The smallest `min_distance` of `Falloff::InverseSquare`, which would otherwise divide zero by zero.
+const MIN_INVERSE_SQUARE_DISTANCE: f32 = 0.001;
+
+/// How the trauma of a `ShakeImpulse` or `ShakeEmitter` decreases with distance.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Falloff {
+    /// Decreases linearly, from full trauma at the source to no trauma at the radius.
+    #[default]
+    Linear,
+    /// Decreases with the inverse square of the distance, and is cut off at the radius.
+    /// Anything closer than `min_distance` receives full trauma.
+    InverseSquare {
+        /// The distance within which full trauma is received.
+        /// Must be positive, and is clamped to at least `0.001`.
+        /// Trauma drops off quickly beyond it, so very small values give almost no trauma outside of the source.
+        min_distance: f32,
+    },
+    /// A custom falloff function.
+    /// Receives the distance divided by the radius (between `0.0` and `1.0`),
+    /// and returns how much of the trauma is received (usually between `0.0` and `1.0`).
+    Custom(fn(f32) -> f32),
+}
+
+impl Falloff {
+    /// Returns how much of the trauma is received at `distance` from a source with the given `radius`.
+    pub fn multiplier(&self, distance: f32, radius: f32) -> f32 {
+        if distance >= radius || radius <= 0.0 {
+            return 0.0;
+        }
+        match self {
+            Falloff::Linear => 1.0 - distance / radius,
+            Falloff::InverseSquare { min_distance } => {
+                let min_distance = f32::max(*min_distance, MIN_INVERSE_SQUARE_DISTANCE);
+                let ratio = min_distance / f32::max(distance, min_distance);
+                ratio * ratio
+            }
+            Falloff::Custom(falloff) => falloff(distance / radius).max(0.0),
+        }
+    }
+}
+
+/// A one-shot burst of trauma at a position in the world, such as an explosion.
+/// Adds trauma to every `Shake2d` and `Shake3d` within `radius`, based on the distance to their `GlobalTransform`.
+/// `Shake2d` entities only take the X and Y coordinates into account.
+/// `Shake3d` entities with a `ShakeDirection` are oriented away from the impulse.
+///
+/// Can either be sent as a buffered event with an `EventWriter<ShakeImpulse>`,
+/// or triggered immediately with `Commands::trigger`.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ShakeImpulse {
+    /// The position of the impulse, in world space.
+    pub position: Vec3,
+    /// The trauma added to shakes at the position of the impulse.
+    pub trauma: f32,
+    /// Shakes further away than this receive no trauma.
+    pub radius: f32,
+    /// How the trauma decreases with distance.
+    pub falloff: Falloff,
+}
+
+impl ShakeImpulse {
+    /// Creates a new `ShakeImpulse` with a linear falloff.
+    pub fn new(position: Vec3, trauma: f32, radius: f32) -> Self {
+        Self {
+            position,
+            trauma,
+            radius,
+            falloff: Falloff::Linear,
+        }
+    }
+
+    /// Sets the falloff of the impulse.
+    pub fn with_falloff(mut self, falloff: Falloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+}
+
+/// A continuous source of shake, such as an engine or a waterfall.
+/// Keeps the trauma of every `Shake2d` and `Shake3d` within `radius` at least as high as
+/// `trauma` scaled by the falloff, based on the distance between the `GlobalTransform`s.
+/// `Shake2d` entities only take the X and Y coordinates into account.
+#[derive(Component, Clone, Copy, Debug)]
+#[require(Transform)]
+pub struct ShakeEmitter {
+    /// The trauma that shakes at the position of the emitter are kept at.
+    /// Defaults to `0.5`.
+    pub trauma: f32,
+    /// Shakes further away than this are not affected.
+    /// Defaults to `10.0`.
+    pub radius: f32,
+    /// How the trauma decreases with distance.
+    /// Defaults to `Falloff::Linear`.
+    pub falloff: Falloff,
+}
+
+impl Default for ShakeEmitter {
+    fn default() -> Self {
+        Self {
+            trauma: 0.5,
+            radius: 10.0,
+            falloff: Falloff::Linear,
+        }
+    }
+}
+
+/// Adds the trauma of an impulse to every shake within its radius.
+/// When `directional`, shakes with a `ShakeDirection` are also oriented away from the impulse.
+fn resolve_positional_trauma(
+    impulse: &ShakeImpulse,
+    mode: TraumaMode,
+    directional: bool,
+    shakes_2d: &mut Shakes2d,
+    shakes_3d: &mut Shakes3d,
+) {
+    for (shake, global_transform, shake_direction) in shakes_2d.iter_mut() {
+        // 2D shakes ignore the depth of the impulse.
+        let offset = (global_transform.translation() - impulse.position).with_z(0.0);
+        let amount = impulse.trauma * impulse.falloff.multiplier(offset.length(), impulse.radius);
+        if amount > 0.0 {
+            add_positional_trauma(shake, shake_direction, amount, mode, directional, offset);
+        }
+    }
+    for (shake, global_transform, shake_direction) in shakes_3d.iter_mut() {
+        let offset = global_transform.translation() - impulse.position;
+        let amount = impulse.trauma * impulse.falloff.multiplier(offset.length(), impulse.radius);
+        if amount > 0.0 {
+            add_positional_trauma(shake, shake_direction, amount, mode, directional, offset);
+        }
+    }
+}
+
+/// Adds positional trauma to a shake.
+/// Non-directional trauma comes from emitters, which hold the trauma up against its decay every frame,
+/// so like the decay it bypasses change detection.
+fn add_positional_trauma<S: ShakeCore>(
+    mut shake: Mut<S>,
+    shake_direction: Option<Mut<ShakeDirection>>,
+    amount: f32,
+    mode: TraumaMode,
+    directional: bool,
+    offset: Vec3,
+) {
+    if directional {
+        add_trauma_to_shake(shake, shake_direction, amount, mode, Some(offset));
+    } else {
+        add_trauma_to_shake(
+            shake.bypass_change_detection(),
+            shake_direction,
+            amount,
+            mode,
+            None,
+        );
+    }
+}
+
+fn apply_impulse(impulse: &ShakeImpulse, shakes_2d: &mut Shakes2d, shakes_3d: &mut Shakes3d) {
+    resolve_positional_trauma(impulse, TraumaMode::Add, true, shakes_2d, shakes_3d);
+}
+
+pub(crate) fn handle_shake_impulse_events(
+    mut events: EventReader<ShakeImpulse>,
+    mut shakes_2d: Shakes2d,
+    mut shakes_3d: Shakes3d,
+) {
+    for impulse in events.read() {
+        apply_impulse(impulse, &mut shakes_2d, &mut shakes_3d);
+    }
+}
+
+pub(crate) fn on_shake_impulse(
+    trigger: Trigger<ShakeImpulse>,
+    mut shakes_2d: Shakes2d,
+    mut shakes_3d: Shakes3d,
+) {
+    apply_impulse(trigger.event(), &mut shakes_2d, &mut shakes_3d);
+}
+
+pub(crate) fn apply_shake_emitters(
+    emitters: Query<(&ShakeEmitter, &GlobalTransform)>,
+    mut shakes_2d: Shakes2d,
+    mut shakes_3d: Shakes3d,
+) {
+    for (emitter, global_transform) in emitters.iter() {
+        let impulse = ShakeImpulse::new(
+            global_transform.translation(),
+            emitter.trauma,
+            emitter.radius,
+        )
+        .with_falloff(emitter.falloff);
+        resolve_positional_trauma(
+            &impulse,
+            TraumaMode::AtLeast,
+            false,
+            &mut shakes_2d,
+            &mut shakes_3d,
+        );
+    }
+}
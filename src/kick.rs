@@ -0,0 +1,113 @@
+//! Directional kicks that overshoot and settle, such as weapon recoil.
+
+use bevy::prelude::{Component, Reflect, ReflectComponent, ReflectDefault, Vec3};
+#[cfg(feature = "serde")]
+use bevy::prelude::{ReflectDeserialize, ReflectSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The longest time step used to integrate the springs, so that stiff springs stay stable at low frame rates.
+const MAX_KICK_STEP: f32 = 1.0 / 120.0;
+/// Springs whose offsets and velocities are all below this value are snapped to rest, as they would otherwise never reach it.
+const KICK_REST_THRESHOLD: f32 = 0.0001;
+
+/// A spring-damper channel on a `Shake2d` or `Shake3d` entity, for directional kicks such as weapon recoil.
+///
+/// Impulses added with `ShakeKick::kick` push the springs, which then overshoot and settle back to zero.
+/// The springs are integrated when the shake is applied, and their offset is added to the noise shake
+/// before `CameraShakeSettings` is applied.
+/// Unlike the noise shake, the offset is not scaled by `max_offset` or `max_yaw_pitch_roll`.
+///
+/// `Shake2d` uses the X and Y of the translation, and the roll.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ShakeKick {
+    /// How strongly the springs pull back to zero. Higher values give a faster, snappier kick.
+    /// Defaults to `300.0`.
+    pub stiffness: f32,
+    /// How quickly the springs lose their energy.
+    /// Values below `2.0 * stiffness.sqrt()` overshoot before settling, higher values settle without overshooting.
+    /// Defaults to `15.0`.
+    pub damping: f32,
+    /// The current translation of the kick.
+    pub translation: Vec3,
+    /// The current velocity of the translation.
+    pub translation_velocity: Vec3,
+    /// The current yaw, pitch and roll of the kick, in radians.
+    pub yaw_pitch_roll: Vec3,
+    /// The current velocity of the yaw, pitch and roll, in radians per second.
+    pub yaw_pitch_roll_velocity: Vec3,
+}
+
+impl Default for ShakeKick {
+    fn default() -> Self {
+        Self {
+            stiffness: 300.0,
+            damping: 15.0,
+            translation: Vec3::ZERO,
+            translation_velocity: Vec3::ZERO,
+            yaw_pitch_roll: Vec3::ZERO,
+            yaw_pitch_roll_velocity: Vec3::ZERO,
+        }
+    }
+}
+
+impl ShakeKick {
+    /// Creates a `ShakeKick` with the given stiffness and damping, at rest.
+    pub fn new(stiffness: f32, damping: f32) -> Self {
+        Self {
+            stiffness,
+            damping,
+            ..Default::default()
+        }
+    }
+
+    /// Kicks the springs, by adding to the velocity of the translation and of the yaw, pitch and roll.
+    pub fn kick(&mut self, translation: Vec3, yaw_pitch_roll: Vec3) {
+        self.translation_velocity += translation;
+        self.yaw_pitch_roll_velocity += yaw_pitch_roll;
+    }
+
+    /// Whether the springs are at rest.
+    pub fn is_at_rest(&self) -> bool {
+        self.translation == Vec3::ZERO
+            && self.translation_velocity == Vec3::ZERO
+            && self.yaw_pitch_roll == Vec3::ZERO
+            && self.yaw_pitch_roll_velocity == Vec3::ZERO
+    }
+
+    /// Advances the springs by `delta_secs` seconds.
+    pub(crate) fn step(&mut self, delta_secs: f32) {
+        if delta_secs <= 0.0 {
+            return;
+        }
+        let steps = f32::ceil(delta_secs / MAX_KICK_STEP);
+        let dt = delta_secs / steps;
+        for _ in 0..steps as u32 {
+            // Semi-implicit Euler, which is stable for springs as long as the step is small enough.
+            self.translation_velocity += (-self.stiffness * self.translation
+                - self.damping * self.translation_velocity)
+                * dt;
+            self.translation += self.translation_velocity * dt;
+            self.yaw_pitch_roll_velocity += (-self.stiffness * self.yaw_pitch_roll
+                - self.damping * self.yaw_pitch_roll_velocity)
+                * dt;
+            self.yaw_pitch_roll += self.yaw_pitch_roll_velocity * dt;
+        }
+        let largest = self
+            .translation
+            .abs()
+            .max(self.translation_velocity.abs())
+            .max(self.yaw_pitch_roll.abs())
+            .max(self.yaw_pitch_roll_velocity.abs())
+            .max_element();
+        if largest < KICK_REST_THRESHOLD {
+            *self = Self::new(self.stiffness, self.damping);
+        }
+    }
+}
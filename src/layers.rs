@@ -0,0 +1,177 @@
+//! Multiple independent shakes on one entity.
+
+use bevy::prelude::{Component, Reflect, ReflectComponent, ReflectDefault};
+#[cfg(feature = "serde")]
+use bevy::prelude::{ReflectDeserialize, ReflectSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    trauma::{apply_trauma, TraumaMode},
+    SampleContext, ShakeSampler, ShakeSource, TraumaDecay,
+};
+
+/// How many seconds apart consecutive layers of a `ShakeLayers` sample their random sources.
+/// Far enough apart for the built-in noise to be unrelated.
+pub const LAYER_TIME_OFFSET: f32 = 64.0;
+
+/// How the layers of a `ShakeLayers` are combined with each other and with the shake's own trauma.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LayerBlend {
+    /// Adds the output of all layers together.
+    #[default]
+    Sum,
+    /// Uses the output with the largest magnitude, per axis.
+    Max,
+}
+
+/// A single named layer of a `ShakeLayers`.
+#[derive(Clone, Debug, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ShakeLayer {
+    /// The name of the layer, used to find it when adding trauma.
+    pub name: String,
+    /// The current trauma of the layer.
+    /// Defaults to `0.0`.
+    pub trauma: f32,
+    /// The exponent of the trauma used when calculating the layer's output.
+    /// Defaults to `2.0`.
+    pub trauma_power: f32,
+    /// How the layer's trauma decreases over time.
+    /// Defaults to `TraumaDecay::Linear(0.8)`.
+    pub decay: TraumaDecay,
+    /// Scales the layer's output, relative to the maximum offset and rotation of the shake.
+    /// Defaults to `1.0`.
+    pub amplitude: f32,
+    /// The random sources of the layer, for all 6 dimensions.
+    /// The first 3 are XYZ lateral motion, the last 3 are yaw/pitch/roll.
+    /// `Shake2d` uses the first, second and last.
+    /// Defaults to Perlin noise, seeded with the index of the axis.
+    /// The sources are sampled at an offset that depends on the index of the layer, see `ShakeLayers`.
+    pub random_sources: [ShakeSource; 6],
+}
+
+impl Default for ShakeLayer {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            trauma: 0.0,
+            trauma_power: 2.0,
+            decay: TraumaDecay::default(),
+            amplitude: 1.0,
+            random_sources: [0, 1, 2, 3, 4, 5].map(ShakeSource::perlin),
+        }
+    }
+}
+
+impl ShakeLayer {
+    /// Creates a new layer with the given name and default parameters.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Additional layers of shake on a `Shake2d` or `Shake3d` entity, such as a constant engine rumble
+/// underneath sudden explosions. Every layer has its own trauma, decay, amplitude and random sources.
+///
+/// The output of every layer is blended with the output of the shake's own trauma,
+/// and scaled by the shake's maximum offset and rotation.
+///
+/// Every layer samples its random sources `LAYER_TIME_OFFSET` seconds further along than the previous one,
+/// so layers with the same sources, such as the default ones, don't move in lockstep.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ShakeLayers {
+    /// The layers.
+    pub layers: Vec<ShakeLayer>,
+    /// How the layers are combined.
+    /// Defaults to `LayerBlend::Sum`.
+    pub blend: LayerBlend,
+}
+
+impl ShakeLayers {
+    /// Creates a new `ShakeLayers` from a list of layers.
+    pub fn new(layers: impl IntoIterator<Item = ShakeLayer>) -> Self {
+        Self {
+            layers: layers.into_iter().collect(),
+            blend: LayerBlend::Sum,
+        }
+    }
+
+    /// Sets how the layers are combined.
+    pub fn with_blend(mut self, blend: LayerBlend) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// Returns the layer with the given name.
+    pub fn layer(&self, name: &str) -> Option<&ShakeLayer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    /// Returns the layer with the given name.
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut ShakeLayer> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    /// Adds trauma to the layer with the given name, clamping it between `0.0` and `1.0`.
+    /// Does nothing if there is no such layer.
+    pub fn add_trauma(&mut self, name: &str, amount: f32) {
+        if let Some(layer) = self.layer_mut(name) {
+            apply_trauma(&mut layer.trauma, amount, TraumaMode::Add);
+        }
+    }
+
+    /// Raises the trauma of the layer with the given name to at least `amount`.
+    /// Does nothing if there is no such layer.
+    pub fn set_trauma_at_least(&mut self, name: &str, amount: f32) {
+        if let Some(layer) = self.layer_mut(name) {
+            apply_trauma(&mut layer.trauma, amount, TraumaMode::AtLeast);
+        }
+    }
+
+    /// Whether every layer has no trauma, and won't gain any by decaying for `delta_secs` seconds.
+    pub(crate) fn is_silent(&self, delta_secs: f32) -> bool {
+        self.layers
+            .iter()
+            .all(|layer| layer.trauma <= 0.0 && layer.decay.decay(0.0, delta_secs) <= 0.0)
+    }
+
+    /// Decays the trauma of every layer by `context.delta`, and blends their output into `output`.
+    /// `axes` maps every element of `output` to the index of the random source used for it.
+    pub(crate) fn sample(&mut self, context: &SampleContext, axes: &[usize], output: &mut [f32]) {
+        for (index, layer) in self.layers.iter_mut().enumerate() {
+            layer.trauma = layer.decay.decay(layer.trauma, context.delta);
+            let trauma_amount = f32::powf(layer.trauma, layer.trauma_power);
+            if trauma_amount <= 0.0 {
+                continue;
+            }
+            for (value, axis) in output.iter_mut().zip(axes) {
+                let context = SampleContext {
+                    axis: *axis,
+                    time: context.time + index as f32 * LAYER_TIME_OFFSET,
+                    trauma: layer.trauma,
+                    ..*context
+                };
+                let sample =
+                    layer.amplitude * trauma_amount * layer.random_sources[*axis].sample(&context);
+                *value = match self.blend {
+                    LayerBlend::Sum => *value + sample,
+                    LayerBlend::Max if sample.abs() > value.abs() => sample,
+                    LayerBlend::Max => *value,
+                };
+            }
+        }
+    }
+}
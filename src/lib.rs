@@ -1,8 +1,59 @@
+#[cfg(feature = "serde")]
+use bevy::prelude::{ReflectDeserialize, ReflectSerialize};
 use bevy::{
-    log::warn,
-    prelude::{Component, EulerRot, Plugin, Quat, Query, Res, Transform, Update, Vec2, Vec3},
-    time::Time,
+    ecs::schedule::{InternedScheduleLabel, ScheduleLabel},
+    prelude::{
+        Component, IntoScheduleConfigs, Plugin, PostUpdate, PreUpdate, Quat, Reflect,
+        ReflectComponent, ReflectDefault, SystemSet, TransformSystem, Vec2, Vec3,
+    },
+    render::camera::CameraUpdateSystem,
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+mod deterministic;
+mod direction;
+mod emitter;
+mod kick;
+mod layers;
+mod pose;
+#[cfg(feature = "profile")]
+mod profile;
+mod projection;
+mod sampler;
+mod settings;
+mod shake;
+mod shake_noise;
+pub mod sources;
+mod sub_view;
+pub mod testing;
+mod timed;
+mod trauma;
+mod validation;
+
+pub use deterministic::{ShakeClock, ShakeSnapshot};
+pub use direction::ShakeDirection;
+pub use emitter::{Falloff, ShakeEmitter, ShakeImpulse};
+pub use kick::ShakeKick;
+pub use layers::{LayerBlend, ShakeLayer, ShakeLayers, LAYER_TIME_OFFSET};
+pub use pose::ShakeFreePose;
+#[cfg(feature = "profile")]
+pub use profile::{
+    ShakeProfile, ShakeProfileHandle, ShakeProfileLoader, ShakeProfileLoaderError,
+    ShakeProfilePlugin,
+};
+pub use projection::ProjectionShake;
+pub use sampler::{CloneShakeSampler, SampleContext, ShakeSampler};
+pub use settings::CameraShakeSettings;
+pub use shake_noise::ShakeNoise;
+#[cfg(feature = "noise")]
+pub use sources::NoiseFnSource;
+pub use sources::{
+    NoiseKind, OpenSimplexNoise, Oscillator, PerlinNoise, ShakeSource, ValueNoise, Waveform,
+};
+pub use timed::{TimedShake, TimedShakeEnd};
+pub use trauma::{AddTrauma, ShakeCommandsExt, TraumaDecay, TraumaMode};
+pub use validation::{ShakeProblem, ShakeProblemReported};
 
 /// A source of randomness for shaking the camera.
 pub trait RandomSource: Send + Sync {
@@ -10,20 +61,95 @@ pub trait RandomSource: Send + Sync {
     fn rand(&self, time: f32) -> f32;
 }
 
+/// Any closure taking the time in seconds is a `RandomSource`, so simple sources don't need a wrapper type.
+///
+/// ```ignore
+/// ShakeSource::custom(|time: f32| (time * 40.0).sin())
+/// ```
+impl<F> RandomSource for F
+where
+    F: Fn(f32) -> f32 + Send + Sync,
+{
+    fn rand(&self, time: f32) -> f32 {
+        self(time)
+    }
+}
+
 /// A not-random RandomSource. Always returns 0.5.
+/// Used as the default random source, and reported by the shake validation when left in place.
 struct NotRandom;
 
 impl RandomSource for NotRandom {
     fn rand(&self, _time: f32) -> f32 {
-        warn!("You need to set a random source for the shaking to work properly!");
         0.5
     }
 }
 
+/// How a shake is applied to the entity it is on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub enum ShakeMode {
+    /// Overwrites the translation and rotation of the `Transform` with the shake every frame.
+    /// The shake should be put on a dedicated entity, parented between the entity it follows and the camera.
+    #[default]
+    Replace,
+    /// Layers the shake on top of whatever gameplay systems wrote to the `Transform` this frame,
+    /// and removes it again before the next frame's `Update`.
+    /// This allows the shake to be put directly on a camera that is also moved by a controller.
+    /// The translation offset is applied in the entity's local space.
+    Additive,
+    /// Leaves the `Transform` untouched, and instead offsets the rendered image through the `Camera`'s sub view,
+    /// so the camera's `Transform` and `GlobalTransform` stay stable, and pixel art doesn't jitter.
+    /// Rays from `Camera::viewport_to_world` and picking use the sub view, so they follow the shaken image.
+    /// The shake must be on the entity with the `Camera`, and the X and Y of the translation are in logical pixels
+    /// of the viewport. The Z translation and the rotation are not applied.
+    /// The shake takes over `Camera::sub_camera_view`, and sets it back to `None` when the shake stops.
+    SubView,
+}
+
+/// The shake that was applied to the `Transform` of a `Shake2d` or `Shake3d` entity this frame.
+/// Added automatically alongside `Shake2d` and `Shake3d`.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct AppliedShake {
+    /// The translation that was added to the `Transform`, in the space of the entity's parent.
+    /// For `ShakeMode::SubView`, the offset of the sub view in logical pixels.
+    pub translation: Vec3,
+    /// The rotation that the `Transform`'s rotation was multiplied with.
+    /// Always `Quat::IDENTITY` for `ShakeMode::SubView`.
+    pub rotation: Quat,
+}
+
+impl Default for AppliedShake {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+        }
+    }
+}
+
 /// The component to be added for a 3d shake.
 /// Will modify the transform of the entity that this component is added to.
 /// The entity must also have a `Transform` component for the shake to work.
-#[derive(Component)]
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+#[require(AppliedShake, ShakeFreePose)]
 pub struct Shake3d {
     /// The maximum amount to translate laterally in all 3 dimensions.
     /// Defaults to `Vec3::new(0.0, 0.0, 0.0)`.
@@ -39,14 +165,16 @@ pub struct Shake3d {
     /// Should likely be set to a value between `2.0` and `3.0`.
     /// Defaults to `2.0`.
     pub trauma_power: f32,
-    /// The percentage to decrease trauma per second.
-    /// If set to 1, there will be no trauma after 1 second. If set to 0, trauma will not decrease over time.
-    /// Defaults to `0.8`.
-    pub decay: f32,
+    /// How trauma decreases over time.
+    /// Defaults to `TraumaDecay::Linear(0.8)`.
+    pub decay: TraumaDecay,
     /// The random sources for all 6 dimensions.
     /// The first 3 are XYZ lateral motion, the last 3 are yaw/pitch/roll.
-    /// Defaults to a `NotRandom`, which always returns `0.5`.
-    pub random_sources: [Box<dyn RandomSource>; 6],
+    /// Defaults to a custom `NotRandom` source, which always returns `0.5` and is reported as a `ShakeProblem`.
+    pub random_sources: [ShakeSource; 6],
+    /// How the shake is applied to the entity.
+    /// Defaults to `ShakeMode::Replace`.
+    pub mode: ShakeMode,
 }
 
 impl Default for Shake3d {
@@ -56,15 +184,9 @@ impl Default for Shake3d {
             max_yaw_pitch_roll: Vec3::new(0.1, 0.1, 0.1),
             trauma: 0.0,
             trauma_power: 2.0,
-            decay: 0.8,
-            random_sources: [
-                Box::new(NotRandom),
-                Box::new(NotRandom),
-                Box::new(NotRandom),
-                Box::new(NotRandom),
-                Box::new(NotRandom),
-                Box::new(NotRandom),
-            ],
+            decay: TraumaDecay::default(),
+            random_sources: Default::default(),
+            mode: ShakeMode::Replace,
         }
     }
 }
@@ -72,7 +194,14 @@ impl Default for Shake3d {
 /// The component to be added for a 2d shake.
 /// Will modify the transform of the entity that this component is added to.
 /// The entity must also have a `Transform` component for the shake to work.
-#[derive(Component)]
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+#[require(AppliedShake, ShakeFreePose)]
 pub struct Shake2d {
     /// The maximum amount of offset in the X and Y dimensions.
     /// Defaults to `Vec2::new(100.0, 100.0)`.
@@ -87,15 +216,16 @@ pub struct Shake2d {
     /// Should likely be set to a value between `2.0` and `3.0`.
     /// Defaults to `2.0`.
     pub trauma_power: f32,
-    /// The percentage to decrease trauma per second.
-    /// If set to 1, there will be no trauma after 1 second. If set to 0, trauma will not decrease over time.
-    /// If set below 0, trauma will *increase* over time, and if set above 1, trauma will decrease very quickly.
-    /// Defaults to `0.8`.
-    pub decay: f32,
+    /// How trauma decreases over time.
+    /// Defaults to `TraumaDecay::Linear(0.8)`.
+    pub decay: TraumaDecay,
     /// The random sources for all 3 dimensions.
     /// The first 2 are for XY lateral motion, the last one is for roll.
-    /// Defaults to a `NotRandom`, which always returns `0.5`.
-    pub random_sources: [Box<dyn RandomSource>; 3],
+    /// Defaults to a custom `NotRandom` source, which always returns `0.5` and is reported as a `ShakeProblem`.
+    pub random_sources: [ShakeSource; 3],
+    /// How the shake is applied to the entity.
+    /// Defaults to `ShakeMode::Replace`.
+    pub mode: ShakeMode,
 }
 
 impl Default for Shake2d {
@@ -105,93 +235,164 @@ impl Default for Shake2d {
             max_roll: 0.1,
             trauma: 0.0,
             trauma_power: 2.0,
-            decay: 0.8,
-            random_sources: [
-                Box::new(NotRandom),
-                Box::new(NotRandom),
-                Box::new(NotRandom),
-            ],
+            decay: TraumaDecay::default(),
+            random_sources: Default::default(),
+            mode: ShakeMode::Replace,
         }
     }
 }
 
-fn apply_shake_3d(mut query: Query<(&mut Transform, &mut Shake3d)>, time: Res<Time>) {
-    for (mut transform, mut shake_settings) in query.iter_mut() {
-        shake_settings.trauma = f32::max(
-            shake_settings.trauma - shake_settings.decay * time.delta_secs(),
-            0.0,
-        );
-
-        let trauma_amount = f32::powf(shake_settings.trauma, shake_settings.trauma_power);
-
-        if trauma_amount > 0.0 {
-            let shake_translation = shake_settings.max_offset
-                * trauma_amount
-                * Vec3::new(
-                    shake_settings.random_sources[0].rand(time.elapsed_secs()),
-                    shake_settings.random_sources[1].rand(time.elapsed_secs()),
-                    shake_settings.random_sources[2].rand(time.elapsed_secs()),
-                );
-
-            let rotation = shake_settings.max_yaw_pitch_roll
-                * trauma_amount
-                * Vec3::new(
-                    shake_settings.random_sources[3].rand(time.elapsed_secs()),
-                    shake_settings.random_sources[4].rand(time.elapsed_secs()),
-                    shake_settings.random_sources[5].rand(time.elapsed_secs()),
-                );
-
-            let shake_rotation =
-                Quat::from_euler(EulerRot::YXZ, rotation.x, rotation.y, rotation.z);
-            transform.translation = shake_translation;
-            transform.rotation = shake_rotation;
-        } else {
-            transform.translation = Vec3::default();
-            transform.rotation = Quat::default();
+/// The system sets of the `CameraShakePlugin`, used to order other systems relative to the shake.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CameraShakeSystems {
+    /// Removes the shake applied last frame from entities using `ShakeMode::Additive`,
+    /// and from `Projection`s shaken by a `ProjectionShake`.
+    /// Runs in the plugin's `remove_schedule`.
+    RemoveShake,
+    /// Adds trauma from `AddTrauma` events, `ShakeImpulse` events and `ShakeEmitter`s.
+    /// Runs in the plugin's `schedule`, before `ApplyShake`.
+    AddTrauma,
+    /// Decays trauma, and applies the shake to the `Transform`s and `Projection`s.
+    /// Runs in the plugin's `schedule`.
+    ApplyShake,
+    /// Updates the `ShakeFreePose` of every shake.
+    /// Always runs in `PostUpdate`, after `TransformSystem::TransformPropagate`.
+    UpdatePose,
+}
+
+/// The CameraShakePlugin runs the systems required to shake something with a `Shake2d` or `Shake3d` component.
+///
+/// By default, the shake is applied in `PostUpdate`, and removed again (for `ShakeMode::Additive`) in `PreUpdate`.
+/// This gives the following ordering guarantees:
+/// - Systems in `Update` see the unshaken `Transform`, and the shake is layered on top of everything they did.
+/// - The shake is applied before `TransformSystem::TransformPropagate`, so the `GlobalTransform` includes
+///   the shake in the same frame.
+/// - Camera-follow systems that run in `PostUpdate` should be ordered `.before(CameraShakeSystems::AddTrauma)`.
+/// - Systems that need the unshaken pose in `PostUpdate` should be ordered `.after(CameraShakeSystems::UpdatePose)`.
+///
+/// The schedules can be changed with `CameraShakePlugin::new` and `with_remove_schedule`.
+/// When `schedule` is `PostUpdate`, the shake is always applied before transform propagation.
+/// To apply the shake in `FixedUpdate`, use `FixedPreUpdate` as the `remove_schedule`.
+pub struct CameraShakePlugin {
+    /// The schedule trauma is added and the shake is applied in.
+    /// Defaults to `PostUpdate`.
+    pub schedule: InternedScheduleLabel,
+    /// The schedule the shake of entities using `ShakeMode::Additive` is removed in.
+    /// Should run before any system that moves those entities.
+    /// Defaults to `PreUpdate`.
+    pub remove_schedule: InternedScheduleLabel,
+}
+
+impl Default for CameraShakePlugin {
+    fn default() -> Self {
+        Self {
+            schedule: PostUpdate.intern(),
+            remove_schedule: PreUpdate.intern(),
         }
     }
 }
 
-fn apply_shake_2d(mut query: Query<(&mut Transform, &mut Shake2d)>, time: Res<Time>) {
-    for (mut transform, mut shake_settings) in query.iter_mut() {
-        shake_settings.trauma = f32::max(
-            shake_settings.trauma - shake_settings.decay * time.delta_secs(),
-            0.0,
-        );
-
-        let trauma_amount = f32::powf(shake_settings.trauma, shake_settings.trauma_power);
-        if trauma_amount > 0.0 {
-            let offset = shake_settings.max_offset
-                * trauma_amount
-                * Vec2::new(
-                    shake_settings.random_sources[0].rand(time.elapsed_secs()),
-                    shake_settings.random_sources[1].rand(time.elapsed_secs()),
-                );
-
-            let shake_translation = Vec3::new(offset.x, offset.y, 0.0);
-
-            let shake_rotation = Quat::from_euler(
-                EulerRot::YXZ,
-                0.0,
-                0.0,
-                shake_settings.max_roll
-                    * trauma_amount
-                    * shake_settings.random_sources[2].rand(time.elapsed_secs()),
-            );
-            transform.translation = shake_translation;
-            transform.rotation = shake_rotation;
-        } else {
-            transform.translation = Vec3::default();
-            transform.rotation = Quat::default();
+impl CameraShakePlugin {
+    /// Creates a `CameraShakePlugin` that applies the shake in the given schedule.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            ..Default::default()
         }
     }
-}
 
-/// The CameraShakePlugin runs the systems required to shake something with a `Shake2d` or `Shake3d` component.
-pub struct CameraShakePlugin;
+    /// Sets the schedule the shake of entities using `ShakeMode::Additive` is removed in.
+    pub fn with_remove_schedule(mut self, remove_schedule: impl ScheduleLabel) -> Self {
+        self.remove_schedule = remove_schedule.intern();
+        self
+    }
+}
 
 impl Plugin for CameraShakePlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(Update, (apply_shake_2d, apply_shake_3d));
+        app.register_type::<Shake2d>()
+            .register_type::<Shake3d>()
+            .register_type::<AppliedShake>()
+            .register_type::<ShakeFreePose>()
+            .register_type::<ShakeLayers>()
+            .register_type::<ShakeClock>()
+            .register_type::<ShakeKick>()
+            .register_type::<ShakeDirection>()
+            .register_type::<TimedShake>()
+            .register_type::<ShakeNoise>()
+            .register_type::<ProjectionShake>()
+            .register_type::<CameraShakeSettings>()
+            .init_resource::<CameraShakeSettings>()
+            .add_event::<AddTrauma>()
+            .add_event::<ShakeImpulse>()
+            .add_event::<ShakeProblemReported>()
+            .add_observer(trauma::on_add_trauma)
+            .add_observer(emitter::on_shake_impulse)
+            .configure_sets(
+                self.schedule,
+                (
+                    CameraShakeSystems::AddTrauma,
+                    CameraShakeSystems::ApplyShake,
+                )
+                    .chain()
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .configure_sets(
+                PostUpdate,
+                CameraShakeSystems::UpdatePose.after(TransformSystem::TransformPropagate),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    pose::update_shake_free_poses::<Shake2d>,
+                    pose::update_shake_free_poses::<Shake3d>,
+                )
+                    .in_set(CameraShakeSystems::UpdatePose),
+            )
+            .add_systems(
+                self.remove_schedule,
+                (
+                    shake::remove_additive_shake::<Shake2d>,
+                    shake::remove_additive_shake::<Shake3d>,
+                    projection::remove_projection_shake,
+                )
+                    .in_set(CameraShakeSystems::RemoveShake),
+            )
+            .add_systems(
+                self.schedule,
+                (
+                    validation::validate_shakes::<Shake2d>,
+                    validation::validate_shakes::<Shake3d>,
+                )
+                    .before(CameraShakeSystems::AddTrauma),
+            )
+            .add_systems(
+                self.schedule,
+                (
+                    trauma::handle_add_trauma_events,
+                    emitter::handle_shake_impulse_events,
+                    emitter::apply_shake_emitters,
+                )
+                    .chain()
+                    .in_set(CameraShakeSystems::AddTrauma),
+            )
+            .add_systems(
+                self.schedule,
+                (
+                    (shake::apply_shake::<Shake2d>, shake::apply_shake::<Shake3d>),
+                    (
+                        projection::apply_projection_shake,
+                        sub_view::apply_sub_view_shake::<Shake2d>,
+                        sub_view::apply_sub_view_shake::<Shake3d>,
+                    )
+                        .before(CameraUpdateSystem),
+                    (
+                        timed::finish_timed_shakes::<Shake2d>,
+                        timed::finish_timed_shakes::<Shake3d>,
+                    ),
+                )
+                    .chain()
+                    .in_set(CameraShakeSystems::ApplyShake),
+            );
     }
 }
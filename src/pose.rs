@@ -0,0 +1,115 @@
+//! The pose of a shake entity without the shake, for aiming, picking and audio.
+
+use bevy::prelude::{
+    Changed, Component, DetectChangesMut, GlobalTransform, Or, Query, Reflect, ReflectComponent,
+    ReflectDefault, Transform,
+};
+
+use crate::{shake::ShakeCore, AppliedShake, ShakeMode};
+
+/// The pose of a `Shake2d` or `Shake3d` entity without the shake, as if it had no trauma.
+/// Added automatically alongside `Shake2d` and `Shake3d`, and updated in `PostUpdate`
+/// in `CameraShakeSystems::UpdatePose`, after transform propagation.
+///
+/// Use it instead of the `GlobalTransform` for anything that shouldn't jitter along with the view,
+/// such as crosshair raycasts or the position of the audio listener.
+/// For the camera itself when it is a child of the shake entity, use `ShakeFreePose::unshake`.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct ShakeFreePose {
+    /// The `GlobalTransform` of the entity without the shake.
+    pub global: GlobalTransform,
+    /// The `GlobalTransform` of the entity with the shake, as rendered this frame.
+    pub shaken: GlobalTransform,
+}
+
+impl Default for ShakeFreePose {
+    fn default() -> Self {
+        Self {
+            global: GlobalTransform::IDENTITY,
+            shaken: GlobalTransform::IDENTITY,
+        }
+    }
+}
+
+impl ShakeFreePose {
+    /// The shake this frame, as a world space transform from the unshaken pose to the shaken pose.
+    pub fn delta(&self) -> Transform {
+        GlobalTransform::from(self.shaken.affine() * self.global.affine().inverse())
+            .compute_transform()
+    }
+
+    /// Removes the shake from the `GlobalTransform` of this entity or one of its descendants,
+    /// such as a camera parented to the shake entity.
+    pub fn unshake(&self, global_transform: &GlobalTransform) -> GlobalTransform {
+        GlobalTransform::from(
+            self.global.affine() * self.shaken.affine().inverse() * global_transform.affine(),
+        )
+    }
+}
+
+/// Returns the `Transform` of a shake entity without the shake recorded in its `AppliedShake`.
+pub(crate) fn unshaken_transform(
+    mode: ShakeMode,
+    transform: &Transform,
+    applied: &AppliedShake,
+) -> Transform {
+    match mode {
+        // The shake is written to the `Transform` as a translation added in the parent's space,
+        // and a rotation multiplied on the right.
+        ShakeMode::Replace | ShakeMode::Additive => Transform {
+            translation: transform.translation - applied.translation,
+            rotation: transform.rotation * applied.rotation.inverse(),
+            scale: transform.scale,
+        },
+        ShakeMode::SubView => *transform,
+    }
+}
+
+/// Computes the `ShakeFreePose` of a shake entity from its shaken `Transform` and `GlobalTransform`.
+fn compute_pose(
+    mode: ShakeMode,
+    transform: &Transform,
+    global_transform: &GlobalTransform,
+    applied: &AppliedShake,
+) -> ShakeFreePose {
+    let unshaken = unshaken_transform(mode, transform, applied);
+    ShakeFreePose {
+        // The parent's `GlobalTransform`, followed by the unshaken local `Transform`.
+        global: GlobalTransform::from(
+            global_transform.affine()
+                * transform.compute_affine().inverse()
+                * unshaken.compute_affine(),
+        ),
+        shaken: *global_transform,
+    }
+}
+
+/// The shakes whose pose may have changed since it was last updated.
+type PoseChanged<S> = Or<(Changed<GlobalTransform>, Changed<AppliedShake>, Changed<S>)>;
+
+/// Updates the `ShakeFreePose` of every moved or shaken entity from its shaken `Transform` and `GlobalTransform`.
+/// Entities at rest are skipped, and only poses that changed are written, so that they don't trigger change detection.
+pub(crate) fn update_shake_free_poses<S: ShakeCore>(
+    mut query: Query<
+        (
+            &Transform,
+            &GlobalTransform,
+            &AppliedShake,
+            &S,
+            &mut ShakeFreePose,
+        ),
+        PoseChanged<S>,
+    >,
+) {
+    query.par_iter_mut().for_each(
+        |(transform, global_transform, applied, shake_settings, mut pose)| {
+            pose.set_if_neq(compute_pose(
+                shake_settings.mode(),
+                transform,
+                global_transform,
+                applied,
+            ));
+        },
+    );
+}
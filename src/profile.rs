@@ -0,0 +1,237 @@
+//! Shake presets loaded from `.shake.ron` files.
+
+use std::fmt;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    ecs::schedule::{InternedScheduleLabel, ScheduleLabel},
+    platform::collections::HashSet,
+    prelude::{
+        App, Asset, AssetApp, AssetEvent, AssetId, Assets, Component, DetectChanges, EventReader,
+        Handle, IntoScheduleConfigs, Plugin, PostUpdate, Query, Ref, Reflect, ReflectComponent,
+        Res, TypePath, Vec2, Vec3,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{CameraShakeSystems, Shake2d, Shake3d, ShakeSource, TraumaDecay};
+
+/// The tunable parameters of a shake, loaded from a `.shake.ron` file.
+///
+/// Entities with a `ShakeProfileHandle` and a `Shake2d` or `Shake3d` have their parameters replaced with the
+/// profile's when it is loaded, and again every time the file changes (when Bevy's `file_watcher` feature is enabled).
+/// The trauma and `ShakeMode` of the shake are left untouched.
+///
+/// `Shake2d` uses the X and Y of `max_offset`, the roll of `max_yaw_pitch_roll`,
+/// and the first, second and last of the `random_sources`.
+///
+/// Any field that is left out of the file uses its default value.
+///
+/// ```ron
+/// (
+///     max_offset: (0.2, 0.2, 0.0),
+///     max_yaw_pitch_roll: (0.05, 0.05, 0.1),
+///     trauma_power: 3.0,
+///     decay: Exponential(half_life: 0.75),
+/// )
+/// ```
+#[derive(Asset, TypePath, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShakeProfile {
+    /// The maximum amount to translate laterally in all 3 dimensions.
+    /// Defaults to `Vec3::new(0.0, 0.0, 0.0)`.
+    pub max_offset: Vec3,
+    /// The maximum amount to rotate in yaw, pitch, and roll.
+    /// Defaults to `Vec3::new(0.1, 0.1, 0.1)`.
+    pub max_yaw_pitch_roll: Vec3,
+    /// The exponent of the trauma used when calculating offset and rotational shakiness.
+    /// Defaults to `2.0`.
+    pub trauma_power: f32,
+    /// How trauma decreases over time.
+    /// Defaults to `TraumaDecay::Linear(0.8)`.
+    pub decay: TraumaDecay,
+    /// The random sources for all 6 dimensions.
+    /// The first 3 are XYZ lateral motion, the last 3 are yaw/pitch/roll.
+    /// Defaults to Perlin noise, seeded with the index of the axis.
+    pub random_sources: [ShakeSource; 6],
+}
+
+impl Default for ShakeProfile {
+    fn default() -> Self {
+        Self {
+            max_offset: Vec3::new(0.0, 0.0, 0.0),
+            max_yaw_pitch_roll: Vec3::new(0.1, 0.1, 0.1),
+            trauma_power: 2.0,
+            decay: TraumaDecay::default(),
+            random_sources: [0, 1, 2, 3, 4, 5].map(ShakeSource::perlin),
+        }
+    }
+}
+
+impl ShakeProfile {
+    /// Overwrites the parameters of a `Shake3d` with this profile.
+    pub fn apply_3d(&self, shake: &mut Shake3d) {
+        shake.max_offset = self.max_offset;
+        shake.max_yaw_pitch_roll = self.max_yaw_pitch_roll;
+        shake.trauma_power = self.trauma_power;
+        shake.decay = self.decay.clone();
+        shake.random_sources = self.random_sources.clone();
+    }
+
+    /// Overwrites the parameters of a `Shake2d` with this profile.
+    pub fn apply_2d(&self, shake: &mut Shake2d) {
+        shake.max_offset = Vec2::new(self.max_offset.x, self.max_offset.y);
+        shake.max_roll = self.max_yaw_pitch_roll.z;
+        shake.trauma_power = self.trauma_power;
+        shake.decay = self.decay.clone();
+        shake.random_sources = [
+            self.random_sources[0].clone(),
+            self.random_sources[1].clone(),
+            self.random_sources[5].clone(),
+        ];
+    }
+}
+
+/// Makes the `Shake2d` or `Shake3d` on this entity use the parameters of a `ShakeProfile`.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct ShakeProfileHandle(pub Handle<ShakeProfile>);
+
+/// An error produced while loading a `ShakeProfile`.
+#[derive(Debug)]
+pub enum ShakeProfileLoaderError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not a valid RON `ShakeProfile`.
+    Ron(ron::error::SpannedError),
+}
+
+impl fmt::Display for ShakeProfileLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShakeProfileLoaderError::Io(error) => {
+                write!(f, "could not read shake profile: {error}")
+            }
+            ShakeProfileLoaderError::Ron(error) => {
+                write!(f, "could not parse shake profile: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShakeProfileLoaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ShakeProfileLoaderError::Io(error) => Some(error),
+            ShakeProfileLoaderError::Ron(error) => Some(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for ShakeProfileLoaderError {
+    fn from(error: std::io::Error) -> Self {
+        ShakeProfileLoaderError::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for ShakeProfileLoaderError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        ShakeProfileLoaderError::Ron(error)
+    }
+}
+
+/// Loads `ShakeProfile`s from `.shake.ron` files.
+#[derive(Default, TypePath)]
+pub struct ShakeProfileLoader;
+
+impl AssetLoader for ShakeProfileLoader {
+    type Asset = ShakeProfile;
+    type Settings = ();
+    type Error = ShakeProfileLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<ShakeProfile, ShakeProfileLoaderError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["shake.ron"]
+    }
+}
+
+/// Copies the parameters of loaded or modified profiles into the shakes that use them.
+fn apply_shake_profiles(
+    mut events: EventReader<AssetEvent<ShakeProfile>>,
+    profiles: Res<Assets<ShakeProfile>>,
+    mut shakes_2d: Query<(Ref<ShakeProfileHandle>, &mut Shake2d)>,
+    mut shakes_3d: Query<(Ref<ShakeProfileHandle>, &mut Shake3d)>,
+) {
+    let updated: HashSet<AssetId<ShakeProfile>> = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (handle, mut shake) in shakes_2d.iter_mut() {
+        if !handle.is_changed() && !updated.contains(&handle.0.id()) {
+            continue;
+        }
+        if let Some(profile) = profiles.get(&handle.0) {
+            profile.apply_2d(&mut shake);
+        }
+    }
+    for (handle, mut shake) in shakes_3d.iter_mut() {
+        if !handle.is_changed() && !updated.contains(&handle.0.id()) {
+            continue;
+        }
+        if let Some(profile) = profiles.get(&handle.0) {
+            profile.apply_3d(&mut shake);
+        }
+    }
+}
+
+/// Adds the `ShakeProfile` asset, its loader, and the system that applies profiles to shakes.
+/// Requires Bevy's `AssetPlugin`, and should be added alongside the `CameraShakePlugin`.
+/// Profiles are applied in `CameraShakeSystems::AddTrauma`, so the schedule should match the one of the `CameraShakePlugin`.
+pub struct ShakeProfilePlugin {
+    /// The schedule profiles are applied in.
+    /// Defaults to `PostUpdate`.
+    pub schedule: InternedScheduleLabel,
+}
+
+impl Default for ShakeProfilePlugin {
+    fn default() -> Self {
+        Self {
+            schedule: PostUpdate.intern(),
+        }
+    }
+}
+
+impl ShakeProfilePlugin {
+    /// Creates a `ShakeProfilePlugin` that applies profiles in the given schedule.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Plugin for ShakeProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<ShakeProfile>()
+            .init_asset_loader::<ShakeProfileLoader>()
+            .register_type::<ShakeProfileHandle>()
+            .add_systems(
+                self.schedule,
+                apply_shake_profiles.in_set(CameraShakeSystems::AddTrauma),
+            );
+    }
+}
@@ -0,0 +1,174 @@
+//! Shaking the camera's projection instead of its `Transform`: field of view punches and orthographic zoom pulses.
+
+#[cfg(feature = "serde")]
+use bevy::prelude::{ReflectDeserialize, ReflectSerialize};
+use bevy::{
+    prelude::{
+        ChildOf, Component, Entity, Projection, Query, Reflect, ReflectComponent, ReflectDefault,
+        Res,
+    },
+    time::Time,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CameraShakeSettings, SampleContext, Shake2d, Shake3d, ShakeClock, ShakeSampler, ShakeSource,
+};
+
+/// Shakes the `Projection` of a camera, driven by the trauma of a `Shake2d` or `Shake3d`.
+///
+/// The trauma is taken from a shake on the same entity, or on the closest ancestor with one,
+/// so it works both with a shake directly on the camera and with a dedicated shake entity parented above it.
+///
+/// The change to the projection is removed again before the next frame's `Update`
+/// (in `CameraShakeSystems::RemoveShake`), so gameplay systems always see the authored value,
+/// and the authored value is restored when the trauma reaches zero.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct ProjectionShake {
+    /// The largest amount added to the field of view of a perspective projection, in radians.
+    /// Use a negative value to zoom in instead of out.
+    /// Defaults to `0.1`.
+    pub max_fov: f32,
+    /// The largest amount added to the scale of an orthographic projection.
+    /// Use a negative value to zoom in instead of out.
+    /// Defaults to `0.1`.
+    pub max_scale: f32,
+    /// The random source used to vary the change over time.
+    /// When `None`, the change is always in the direction of `max_fov` and `max_scale`, which gives a "punch".
+    /// Defaults to `None`.
+    pub random_source: Option<ShakeSource>,
+    /// The amount that was added to the field of view or scale this frame.
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    applied: f32,
+}
+
+impl Default for ProjectionShake {
+    fn default() -> Self {
+        Self {
+            max_fov: 0.1,
+            max_scale: 0.1,
+            random_source: None,
+            applied: 0.0,
+        }
+    }
+}
+
+impl ProjectionShake {
+    /// Creates a `ProjectionShake` that punches the field of view and orthographic scale outwards.
+    pub fn punch(max_fov: f32, max_scale: f32) -> Self {
+        Self {
+            max_fov,
+            max_scale,
+            ..Default::default()
+        }
+    }
+
+    /// Varies the change over time using the given random source, instead of always punching outwards.
+    pub fn with_random_source(mut self, random_source: ShakeSource) -> Self {
+        self.random_source = Some(random_source);
+        self
+    }
+
+    /// The amount that was added to the field of view or orthographic scale this frame.
+    pub fn applied(&self) -> f32 {
+        self.applied
+    }
+}
+
+/// Returns the value of the projection that is shaken, and the largest amount that can be added to it.
+fn shaken_value<'a>(
+    projection: &'a mut Projection,
+    shake: &ProjectionShake,
+) -> Option<(&'a mut f32, f32)> {
+    match projection {
+        Projection::Perspective(perspective) => Some((&mut perspective.fov, shake.max_fov)),
+        Projection::Orthographic(orthographic) => Some((&mut orthographic.scale, shake.max_scale)),
+        Projection::Custom(_) => None,
+    }
+}
+
+/// Removes the change applied last frame, restoring the authored projection.
+pub(crate) fn remove_projection_shake(mut query: Query<(&mut Projection, &mut ProjectionShake)>) {
+    for (mut projection, mut shake) in query.iter_mut() {
+        if shake.applied == 0.0 {
+            continue;
+        }
+        if let Some((value, _)) = shaken_value(&mut projection, &shake) {
+            *value -= shake.applied;
+        }
+        shake.applied = 0.0;
+    }
+}
+
+/// Returns the shake entity, its trauma, and its trauma power, for the shake on `entity` or its closest ancestor.
+fn find_shake(
+    entity: Entity,
+    shakes_2d: &Query<&Shake2d>,
+    shakes_3d: &Query<&Shake3d>,
+    parents: &Query<&ChildOf>,
+) -> Option<(Entity, f32, f32)> {
+    for entity in std::iter::once(entity).chain(parents.iter_ancestors(entity)) {
+        if let Ok(shake) = shakes_2d.get(entity) {
+            return Some((entity, shake.trauma, shake.trauma_power));
+        }
+        if let Ok(shake) = shakes_3d.get(entity) {
+            return Some((entity, shake.trauma, shake.trauma_power));
+        }
+    }
+    None
+}
+
+/// Shakes the projection of every `ProjectionShake` camera.
+/// Runs after the trauma of the shakes has decayed, and before the camera's matrices are updated.
+pub(crate) fn apply_projection_shake(
+    mut query: Query<(Entity, &mut Projection, &mut ProjectionShake)>,
+    shakes_2d: Query<&Shake2d>,
+    shakes_3d: Query<&Shake3d>,
+    parents: Query<&ChildOf>,
+    clocks: Query<&ShakeClock>,
+    time: Res<Time>,
+    settings: Res<CameraShakeSettings>,
+) {
+    for (entity, mut projection, mut shake) in query.iter_mut() {
+        let (shake_entity, trauma, trauma_power) =
+            find_shake(entity, &shakes_2d, &shakes_3d, &parents).unwrap_or((entity, 0.0, 1.0));
+        let trauma_amount = f32::powf(trauma, trauma_power);
+        if trauma_amount <= 0.0 && shake.applied == 0.0 {
+            // Avoids triggering change detection on the projection while there is no shake.
+            continue;
+        }
+        let amount = if trauma_amount > 0.0 {
+            // Follows the clock of the shake, so that it is deterministic too.
+            let sample_time = clocks
+                .get(shake_entity)
+                .map_or(time.elapsed_secs(), ShakeClock::sample_time);
+            let context = SampleContext {
+                entity: shake_entity,
+                axis: 0,
+                time: sample_time,
+                delta: time.delta_secs(),
+                trauma,
+            };
+            let sample = shake
+                .random_source
+                .as_mut()
+                .map_or(1.0, |source| source.sample(&context));
+            trauma_amount * sample * settings.rotation_scale()
+        } else {
+            0.0
+        };
+        if let Some((value, max)) = shaken_value(&mut projection, &shake) {
+            // Removes whatever is left over, in case the shake runs more often than it is removed.
+            *value += max * amount - shake.applied;
+            shake.applied = max * amount;
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Random sources that know what they are sampled for, and can hold mutable state.
+
+use bevy::prelude::Entity;
+
+/// What a `ShakeSampler` is sampled for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SampleContext {
+    /// The entity of the shake.
+    /// `Entity::PLACEHOLDER` when sampled through `RandomSource::rand`.
+    pub entity: Entity,
+    /// The index of the source in the `random_sources` it belongs to.
+    pub axis: usize,
+    /// The time to sample at, in seconds. Follows the `ShakeClock` of the shake, if it has one,
+    /// and is offset by `LAYER_TIME_OFFSET` for every layer of a `ShakeLayers`.
+    pub time: f32,
+    /// The number of seconds since the last frame.
+    pub delta: f32,
+    /// The trauma of the shake (or of the `ShakeLayer`, for layer sources), after this frame's decay.
+    pub trauma: f32,
+}
+
+impl SampleContext {
+    /// A context that only knows the time, for sampling outside of a shake.
+    pub fn at_time(time: f32) -> Self {
+        Self {
+            entity: Entity::PLACEHOLDER,
+            axis: 0,
+            time,
+            delta: 0.0,
+            trauma: 0.0,
+        }
+    }
+}
+
+/// A source of randomness for shaking the camera, which receives the context of every sample
+/// and can hold mutable state, such as an RNG or a filter.
+///
+/// Use it through `ShakeSource::sampler`. Every `RandomSource` keeps working through `ShakeSource::custom`,
+/// which samples it with the context's time.
+///
+/// Samplers must be `Clone`, so that every clone of a shake gets its own copy of the sampler and its state.
+pub trait ShakeSampler: Send + Sync + CloneShakeSampler {
+    /// Produces a random float between -1.0 and 1.0.
+    fn sample(&mut self, context: &SampleContext) -> f32;
+}
+
+/// Clones a boxed `ShakeSampler`. Implemented for every `ShakeSampler` that is `Clone`.
+pub trait CloneShakeSampler {
+    /// Clones the sampler and its state into a new box.
+    fn clone_box(&self) -> Box<dyn ShakeSampler>;
+}
+
+impl<T: ShakeSampler + Clone + 'static> CloneShakeSampler for T {
+    fn clone_box(&self) -> Box<dyn ShakeSampler> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn ShakeSampler> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
@@ -0,0 +1,83 @@
+//! Global shake settings, such as a "screen shake" slider in an options menu.
+
+use bevy::prelude::{Reflect, ReflectDefault, ReflectResource, Resource, Vec3};
+#[cfg(feature = "serde")]
+use bevy::prelude::{ReflectDeserialize, ReflectSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Settings that apply to every `Shake2d` and `Shake3d`.
+/// Inserted by the `CameraShakePlugin`, and meant to be exposed to players in an options menu.
+#[derive(Resource, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Resource, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct CameraShakeSettings {
+    /// Multiplies the translation and rotation of every shake.
+    /// Set to `0.0` to disable shake entirely.
+    /// Defaults to `1.0`.
+    pub intensity: f32,
+    /// The largest translation a shake is allowed to apply along each axis, after `intensity` is applied.
+    /// `Shake2d` uses the X and Y.
+    /// Defaults to `Vec3::INFINITY`.
+    pub max_offset: Vec3,
+    /// The largest yaw, pitch and roll a shake is allowed to apply in radians, after `intensity` is applied.
+    /// Set an axis to `0.0` to disable it, for example `Vec3::new(f32::INFINITY, f32::INFINITY, 0.0)` disables roll.
+    /// `Shake2d` uses the roll.
+    /// Defaults to `Vec3::INFINITY`.
+    pub max_yaw_pitch_roll: Vec3,
+    /// Reduces the shake for players who are sensitive to motion.
+    /// Disables rotation, and multiplies translation by `reduced_motion_intensity`.
+    /// Defaults to `false`.
+    pub reduced_motion: bool,
+    /// Multiplies the translation of every shake when `reduced_motion` is enabled, on top of `intensity`.
+    /// Defaults to `0.0`, which disables shake entirely.
+    pub reduced_motion_intensity: f32,
+}
+
+impl Default for CameraShakeSettings {
+    fn default() -> Self {
+        Self {
+            intensity: 1.0,
+            max_offset: Vec3::INFINITY,
+            max_yaw_pitch_roll: Vec3::INFINITY,
+            reduced_motion: false,
+            reduced_motion_intensity: 0.0,
+        }
+    }
+}
+
+impl CameraShakeSettings {
+    /// How much the translation of every shake is multiplied by.
+    pub fn translation_scale(&self) -> f32 {
+        if self.reduced_motion {
+            self.intensity * self.reduced_motion_intensity
+        } else {
+            self.intensity
+        }
+    }
+
+    /// How much the rotation of every shake is multiplied by.
+    pub fn rotation_scale(&self) -> f32 {
+        if self.reduced_motion {
+            0.0
+        } else {
+            self.intensity
+        }
+    }
+
+    /// Scales and caps the translation of a shake.
+    pub fn limit_translation(&self, translation: Vec3) -> Vec3 {
+        let max = self.max_offset.abs();
+        (translation * self.translation_scale()).min(max).max(-max)
+    }
+
+    /// Scales and caps the yaw, pitch and roll of a shake.
+    pub fn limit_yaw_pitch_roll(&self, yaw_pitch_roll: Vec3) -> Vec3 {
+        let max = self.max_yaw_pitch_roll.abs();
+        (yaw_pitch_roll * self.rotation_scale()).min(max).max(-max)
+    }
+}
@@ -0,0 +1,359 @@
+//! The shake core shared by `Shake2d` and `Shake3d`: trauma, decay, sampling and applying the offset.
+//!
+//! `Shake2d` and `Shake3d` only differ in which channels they shake, so the systems in this module
+//! are generic over `ShakeCore`, and every feature works the same for both.
+
+use bevy::{
+    ecs::component::Mutable,
+    prelude::{
+        ChildOf, Component, DetectChangesMut, Entity, EulerRot, GlobalTransform, Mut, Quat, Query,
+        Res, Transform, Vec3,
+    },
+    time::Time,
+};
+
+use crate::{
+    pose::unshaken_transform, AppliedShake, CameraShakeSettings, SampleContext, Shake2d, Shake3d,
+    ShakeClock, ShakeDirection, ShakeKick, ShakeLayers, ShakeMode, ShakeNoise, ShakeProblem,
+    ShakeSampler, ShakeSource, TimedShake, TraumaDecay,
+};
+
+/// The settings shared by every shake component, and how its channels map to a translation and rotation.
+pub(crate) trait ShakeCore: Component<Mutability = Mutable> {
+    /// The name of the component, for log messages.
+    const NAME: &'static str;
+    /// The indices of the `ShakeLayer` random sources used for each of the shake's random sources.
+    const LAYER_AXES: &'static [usize];
+
+    fn trauma(&self) -> f32;
+    fn trauma_mut(&mut self) -> &mut f32;
+    fn trauma_power(&self) -> f32;
+    fn decay(&self) -> &TraumaDecay;
+    fn random_sources_mut(&mut self) -> &mut [ShakeSource];
+    fn mode(&self) -> ShakeMode;
+    /// Returns the problems with the configuration of this shake.
+    fn problems(&self) -> Vec<ShakeProblem>;
+
+    /// Scales the amount sampled for every random source to a translation and yaw, pitch and roll.
+    fn offset(&self, amounts: &[f32]) -> (Vec3, Vec3);
+
+    /// Removes the parts of a translation and yaw, pitch and roll that this kind of shake doesn't move along.
+    fn restrict(translation: Vec3, yaw_pitch_roll: Vec3) -> (Vec3, Vec3);
+}
+
+impl ShakeCore for Shake3d {
+    const NAME: &'static str = "Shake3d";
+    const LAYER_AXES: &'static [usize] = &[0, 1, 2, 3, 4, 5];
+
+    fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    fn trauma_mut(&mut self) -> &mut f32 {
+        &mut self.trauma
+    }
+
+    fn trauma_power(&self) -> f32 {
+        self.trauma_power
+    }
+
+    fn decay(&self) -> &TraumaDecay {
+        &self.decay
+    }
+
+    fn random_sources_mut(&mut self) -> &mut [ShakeSource] {
+        &mut self.random_sources
+    }
+
+    fn mode(&self) -> ShakeMode {
+        self.mode
+    }
+
+    fn problems(&self) -> Vec<ShakeProblem> {
+        self.validate()
+    }
+
+    fn offset(&self, amounts: &[f32]) -> (Vec3, Vec3) {
+        (
+            self.max_offset * Vec3::new(amounts[0], amounts[1], amounts[2]),
+            self.max_yaw_pitch_roll * Vec3::new(amounts[3], amounts[4], amounts[5]),
+        )
+    }
+
+    fn restrict(translation: Vec3, yaw_pitch_roll: Vec3) -> (Vec3, Vec3) {
+        (translation, yaw_pitch_roll)
+    }
+}
+
+impl ShakeCore for Shake2d {
+    const NAME: &'static str = "Shake2d";
+    // X, Y and roll.
+    const LAYER_AXES: &'static [usize] = &[0, 1, 5];
+
+    fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    fn trauma_mut(&mut self) -> &mut f32 {
+        &mut self.trauma
+    }
+
+    fn trauma_power(&self) -> f32 {
+        self.trauma_power
+    }
+
+    fn decay(&self) -> &TraumaDecay {
+        &self.decay
+    }
+
+    fn random_sources_mut(&mut self) -> &mut [ShakeSource] {
+        &mut self.random_sources
+    }
+
+    fn mode(&self) -> ShakeMode {
+        self.mode
+    }
+
+    fn problems(&self) -> Vec<ShakeProblem> {
+        self.validate()
+    }
+
+    fn offset(&self, amounts: &[f32]) -> (Vec3, Vec3) {
+        (
+            Vec3::new(
+                self.max_offset.x * amounts[0],
+                self.max_offset.y * amounts[1],
+                0.0,
+            ),
+            Vec3::new(0.0, 0.0, self.max_roll * amounts[2]),
+        )
+    }
+
+    fn restrict(translation: Vec3, yaw_pitch_roll: Vec3) -> (Vec3, Vec3) {
+        (
+            translation.with_z(0.0),
+            Vec3::new(0.0, 0.0, yaw_pitch_roll.z),
+        )
+    }
+}
+
+/// Applies a shake offset to a `Transform`, and records it in the entity's `AppliedShake`.
+/// Only writes the components when they change, so that shakes at rest don't trigger change detection.
+fn apply_offset(
+    mode: ShakeMode,
+    transform: &mut Mut<Transform>,
+    applied: &mut Mut<AppliedShake>,
+    translation: Vec3,
+    rotation: Quat,
+) {
+    match mode {
+        ShakeMode::Replace => {
+            transform.set_if_neq(Transform {
+                translation,
+                rotation,
+                scale: transform.scale,
+            });
+            applied.set_if_neq(AppliedShake {
+                translation,
+                rotation,
+            });
+        }
+        ShakeMode::Additive => {
+            let translation = transform.rotation * translation;
+            transform.set_if_neq(Transform {
+                translation: transform.translation + translation,
+                rotation: transform.rotation * rotation,
+                scale: transform.scale,
+            });
+            applied.set_if_neq(AppliedShake {
+                translation,
+                rotation,
+            });
+        }
+        ShakeMode::SubView => {
+            // Applied to the camera by `sub_view::apply_sub_view_shake`.
+            applied.set_if_neq(AppliedShake {
+                translation,
+                rotation: Quat::IDENTITY,
+            });
+        }
+    }
+}
+
+/// Removes a shake offset recorded in an `AppliedShake` from a `Transform`.
+fn remove_offset(mode: ShakeMode, transform: &mut Mut<Transform>, applied: &mut Mut<AppliedShake>) {
+    if mode != ShakeMode::Additive || **applied == AppliedShake::default() {
+        return;
+    }
+    transform.translation -= applied.translation;
+    transform.rotation *= applied.rotation.inverse();
+    **applied = AppliedShake::default();
+}
+
+/// Removes the shake applied last frame from entities using `ShakeMode::Additive`,
+/// so that gameplay systems see the unshaken `Transform`.
+pub(crate) fn remove_additive_shake<S: ShakeCore>(
+    mut query: Query<(&mut Transform, &mut AppliedShake, &S)>,
+) {
+    query
+        .par_iter_mut()
+        .for_each(|(mut transform, mut applied, shake_settings)| {
+            remove_offset(shake_settings.mode(), &mut transform, &mut applied);
+        });
+}
+
+/// The components used to apply a shake.
+type ShakeData<S> = (
+    Entity,
+    &'static mut Transform,
+    &'static mut AppliedShake,
+    &'static mut S,
+    Option<&'static mut ShakeLayers>,
+    Option<&'static mut ShakeClock>,
+    Option<&'static mut ShakeKick>,
+    Option<&'static mut TimedShake>,
+    Option<&'static ShakeNoise>,
+);
+
+/// The components used to orient a shake with a `ShakeDirection`.
+type DirectionData = Option<(&'static mut ShakeDirection, Option<&'static ChildOf>)>;
+
+/// Decays the trauma of every shake, and applies the shake to its entity.
+/// Shakes are applied in parallel, and shakes at rest are skipped without triggering change detection,
+/// so that thousands of entities can shake at once.
+pub(crate) fn apply_shake<S: ShakeCore>(
+    mut query: Query<(ShakeData<S>, DirectionData)>,
+    parents: Query<&GlobalTransform>,
+    time: Res<Time>,
+    settings: Res<CameraShakeSettings>,
+) {
+    let delta = time.delta_secs();
+    query.par_iter_mut().for_each(
+        |(
+            (
+                entity,
+                mut transform,
+                mut applied,
+                mut shake_settings,
+                layers,
+                clock,
+                kick,
+                timed,
+                noise,
+            ),
+            direction,
+        )| {
+            // Decay and the state of samplers bypass change detection, so that `Changed<S>` is only
+            // triggered by changes made outside of this system, and shakes aren't validated again every frame.
+            let trauma = shake_settings.decay().decay(shake_settings.trauma(), delta);
+            if trauma != shake_settings.trauma() {
+                *shake_settings.bypass_change_detection().trauma_mut() = trauma;
+            }
+
+            let trauma_amount = f32::powf(trauma, shake_settings.trauma_power());
+
+            let sample_time = match clock {
+                Some(mut clock) => {
+                    clock.elapsed += delta;
+                    clock.sample_time()
+                }
+                None => time.elapsed_secs(),
+            };
+
+            let at_rest = trauma_amount <= 0.0
+                && layers.as_ref().is_none_or(|layers| layers.is_silent(delta))
+                && kick.as_ref().is_none_or(|kick| kick.is_at_rest())
+                && timed.is_none()
+                && direction
+                    .as_ref()
+                    .is_none_or(|(direction, _)| direction.direction == Vec3::ZERO);
+            if at_rest {
+                apply_offset(
+                    shake_settings.mode(),
+                    &mut transform,
+                    &mut applied,
+                    Vec3::ZERO,
+                    Quat::IDENTITY,
+                );
+                return;
+            }
+
+            // A `TimedShake` drives the shake with its envelope, independently of the decaying trauma.
+            let amplitude = match timed {
+                Some(mut timed) => f32::max(trauma_amount, timed.tick(delta)),
+                None => trauma_amount,
+            };
+
+            // How far along each axis to shake, between -1.0 and 1.0 for a single layer.
+            let mut amounts = [0.0; 6];
+            let amounts = &mut amounts[..S::LAYER_AXES.len()];
+            if let (true, Some(noise)) = (amplitude > 0.0, noise) {
+                noise.sample(sample_time, amounts);
+                for amount in amounts.iter_mut() {
+                    *amount *= amplitude;
+                }
+            } else if amplitude > 0.0 {
+                let sources = shake_settings
+                    .bypass_change_detection()
+                    .random_sources_mut();
+                for (axis, (amount, source)) in amounts.iter_mut().zip(sources).enumerate() {
+                    let context = SampleContext {
+                        entity,
+                        axis,
+                        time: sample_time,
+                        delta,
+                        trauma,
+                    };
+                    *amount = amplitude * source.sample(&context);
+                }
+            }
+            if let Some(mut layers) = layers {
+                let context = SampleContext {
+                    entity,
+                    axis: 0,
+                    time: sample_time,
+                    delta,
+                    trauma: 0.0,
+                };
+                layers.sample(&context, S::LAYER_AXES, amounts);
+            }
+
+            let (mut translation, mut yaw_pitch_roll) = shake_settings.offset(amounts);
+            if let Some((mut direction, child_of)) = direction {
+                if trauma <= 0.0 && direction.direction != Vec3::ZERO {
+                    direction.direction = Vec3::ZERO;
+                }
+                // The unshaken orientation, from this frame's `Transform` and the parent's `GlobalTransform`.
+                // The parent's own movement this frame is only propagated after the shake, so it lags a frame.
+                let parent_rotation = child_of
+                    .and_then(|child_of| parents.get(child_of.parent()).ok())
+                    .map_or(Quat::IDENTITY, GlobalTransform::rotation);
+                let rotation = parent_rotation
+                    * unshaken_transform(shake_settings.mode(), &transform, &applied).rotation;
+                (translation, yaw_pitch_roll) =
+                    direction.bias(rotation, translation, yaw_pitch_roll);
+            }
+            if let Some(mut kick) = kick {
+                // Checked first to avoid triggering change detection while the kick is at rest.
+                if !kick.is_at_rest() {
+                    kick.step(delta);
+                }
+                translation += kick.translation;
+                yaw_pitch_roll += kick.yaw_pitch_roll;
+            }
+            let (translation, yaw_pitch_roll) = S::restrict(translation, yaw_pitch_roll);
+
+            let shake_translation = settings.limit_translation(translation);
+            let rotation = settings.limit_yaw_pitch_roll(yaw_pitch_roll);
+            let shake_rotation =
+                Quat::from_euler(EulerRot::YXZ, rotation.x, rotation.y, rotation.z);
+            apply_offset(
+                shake_settings.mode(),
+                &mut transform,
+                &mut applied,
+                shake_translation,
+                shake_rotation,
+            );
+        },
+    );
+}
@@ -0,0 +1,617 @@
+//! Built-in [`RandomSource`] implementations, and the reflectable `ShakeSource` description used by the shake components.
+//!
+//! All of the built-in sources are smooth, seeded noise functions sampled over time, so they can be used for
+//! `Shake2d` and `Shake3d` without pulling in an external noise crate.
+//! Give each axis a different seed, otherwise all axes will move in lockstep.
+//!
+//! For periodic motion, such as the hum of an elevator or the vibration of a helicopter, use an `Oscillator`.
+//! With the `noise` feature, any 2D noise function of the `noise` crate can be used through a `NoiseFnSource`.
+
+use std::{
+    f32::consts::TAU,
+    fmt,
+    sync::{Arc, LazyLock},
+};
+
+#[cfg(feature = "noise")]
+use bevy::prelude::Vec2;
+use bevy::prelude::{Reflect, ReflectDefault};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{NotRandom, RandomSource, SampleContext, ShakeSampler};
+
+/// The default frequency of the built-in noise sources, in samples per second.
+pub const DEFAULT_FREQUENCY: f32 = 15.0;
+
+/// Hashes a lattice coordinate together with a seed into 32 pseudo-random bits.
+fn hash(seed: u32, x: i32, y: i32) -> u32 {
+    let mut h = seed.wrapping_mul(0x27D4_EB2F)
+        ^ (x as u32).wrapping_mul(0x9E37_79B1)
+        ^ (y as u32).wrapping_mul(0x85EB_CA77);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2C1B_3C6D);
+    h ^= h >> 12;
+    h = h.wrapping_mul(0x297A_2D39);
+    h ^= h >> 15;
+    h
+}
+
+/// Maps 32 hashed bits to a float between -1.0 and 1.0.
+fn hash_to_unit(h: u32) -> f32 {
+    (h >> 8) as f32 / (1u32 << 23) as f32 - 1.0
+}
+
+/// Quintic fade curve used to smoothly interpolate between lattice points.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+/// 1D Perlin (gradient) noise.
+/// Passes through zero at every lattice point, which gives it a slightly "springy" feel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PerlinNoise {
+    /// The seed of the noise. Sources with different seeds produce unrelated motion.
+    pub seed: u32,
+    /// How many lattice points are crossed per second.
+    /// Higher values produce a faster, more jittery shake.
+    /// Defaults to `15.0`.
+    pub frequency: f32,
+}
+
+impl PerlinNoise {
+    /// Creates a new `PerlinNoise` with the given seed and the default frequency.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            frequency: DEFAULT_FREQUENCY,
+        }
+    }
+
+    /// Sets the frequency of the noise.
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Samples the noise at the given position. Returns a value between -1.0 and 1.0.
+    pub fn sample(&self, x: f32) -> f32 {
+        self.sample_channel(x, 0)
+    }
+
+    /// Samples an independent channel of the noise, which has its own gradients for the same seed.
+    pub(crate) fn sample_channel(&self, x: f32, channel: i32) -> f32 {
+        let i = x.floor();
+        let f = x - i;
+        let i = i as i32;
+        let g0 = hash_to_unit(hash(self.seed, i, channel));
+        let g1 = hash_to_unit(hash(self.seed, i.wrapping_add(1), channel));
+        let n0 = g0 * f;
+        let n1 = g1 * (f - 1.0);
+        // The largest possible value of 1D gradient noise with gradients in [-1, 1] is 0.5.
+        ((n0 + (n1 - n0) * fade(f)) * 2.0).clamp(-1.0, 1.0)
+    }
+}
+
+impl RandomSource for PerlinNoise {
+    fn rand(&self, time: f32) -> f32 {
+        self.sample(time * self.frequency)
+    }
+}
+
+/// Value noise.
+/// Picks a random value at every lattice point and smoothly interpolates between them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ValueNoise {
+    /// The seed of the noise. Sources with different seeds produce unrelated motion.
+    pub seed: u32,
+    /// How many lattice points are crossed per second.
+    /// Higher values produce a faster, more jittery shake.
+    /// Defaults to `15.0`.
+    pub frequency: f32,
+}
+
+impl ValueNoise {
+    /// Creates a new `ValueNoise` with the given seed and the default frequency.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            frequency: DEFAULT_FREQUENCY,
+        }
+    }
+
+    /// Sets the frequency of the noise.
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Samples the noise at the given position. Returns a value between -1.0 and 1.0.
+    pub fn sample(&self, x: f32) -> f32 {
+        self.sample_channel(x, 0)
+    }
+
+    /// Samples an independent channel of the noise, which has its own values for the same seed.
+    pub(crate) fn sample_channel(&self, x: f32, channel: i32) -> f32 {
+        let i = x.floor();
+        let f = x - i;
+        let i = i as i32;
+        let v0 = hash_to_unit(hash(self.seed, i, channel));
+        let v1 = hash_to_unit(hash(self.seed, i.wrapping_add(1), channel));
+        v0 + (v1 - v0) * fade(f)
+    }
+}
+
+impl RandomSource for ValueNoise {
+    fn rand(&self, time: f32) -> f32 {
+        self.sample(time * self.frequency)
+    }
+}
+
+/// OpenSimplex2 noise, sampled along a line through the 2D noise field.
+/// Has fewer directional artifacts than Perlin noise.
+/// Uses its own hash, so it doesn't produce the same values as `noise::OpenSimplex` for the same seed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OpenSimplexNoise {
+    /// The seed of the noise. Sources with different seeds produce unrelated motion.
+    pub seed: u32,
+    /// How many units of the noise field are crossed per second.
+    /// Higher values produce a faster, more jittery shake.
+    /// Defaults to `15.0`.
+    pub frequency: f32,
+}
+
+const SKEW_2D: f32 = 0.366_025_4;
+const UNSKEW_2D: f32 = -0.211_324_87;
+const RSQUARED_2D: f32 = 0.5;
+/// Scales the raw noise value so that it covers roughly -1.0 to 1.0.
+const NORMALIZER_2D: f32 = 99.837_8;
+
+impl OpenSimplexNoise {
+    /// Creates a new `OpenSimplexNoise` with the given seed and the default frequency.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            frequency: DEFAULT_FREQUENCY,
+        }
+    }
+
+    /// Sets the frequency of the noise.
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Samples the 2D noise field at the given position. Returns a value between -1.0 and 1.0.
+    pub fn sample(&self, x: f32, y: f32) -> f32 {
+        // Skew the input onto the simplex lattice.
+        let s = SKEW_2D * (x + y);
+        let xs = x + s;
+        let ys = y + s;
+
+        let xsb = xs.floor();
+        let ysb = ys.floor();
+        let xi = xs - xsb;
+        let yi = ys - ysb;
+        let xsb = xsb as i32;
+        let ysb = ysb as i32;
+
+        // Unskew back to find the offset from the base vertex.
+        let t = (xi + yi) * UNSKEW_2D;
+        let dx0 = xi + t;
+        let dy0 = yi + t;
+
+        let mut value = self.contribution(xsb, ysb, dx0, dy0);
+
+        let a1 = (2.0 * (1.0 + 2.0 * UNSKEW_2D) * (1.0 / UNSKEW_2D + 2.0)) * t
+            + (-2.0 * (1.0 + 2.0 * UNSKEW_2D) * (1.0 + 2.0 * UNSKEW_2D)
+                + (RSQUARED_2D - dx0 * dx0 - dy0 * dy0));
+        if a1 > 0.0 {
+            let dx1 = dx0 - (1.0 + 2.0 * UNSKEW_2D);
+            let dy1 = dy0 - (1.0 + 2.0 * UNSKEW_2D);
+            value += self.contribution(xsb.wrapping_add(1), ysb.wrapping_add(1), dx1, dy1);
+        }
+
+        if dy0 > dx0 {
+            let dx2 = dx0 - UNSKEW_2D;
+            let dy2 = dy0 - (UNSKEW_2D + 1.0);
+            value += self.contribution(xsb, ysb.wrapping_add(1), dx2, dy2);
+        } else {
+            let dx2 = dx0 - (UNSKEW_2D + 1.0);
+            let dy2 = dy0 - UNSKEW_2D;
+            value += self.contribution(xsb.wrapping_add(1), ysb, dx2, dy2);
+        }
+
+        (value * NORMALIZER_2D).clamp(-1.0, 1.0)
+    }
+
+    /// The contribution of a single lattice vertex, given the offset from it.
+    fn contribution(&self, x: i32, y: i32, dx: f32, dy: f32) -> f32 {
+        let a = RSQUARED_2D - dx * dx - dy * dy;
+        if a <= 0.0 {
+            return 0.0;
+        }
+        let angle = hash(self.seed, x, y) as f32 * (std::f32::consts::TAU / u32::MAX as f32);
+        let (gy, gx) = angle.sin_cos();
+        let a2 = a * a;
+        a2 * a2 * (gx * dx + gy * dy)
+    }
+}
+
+impl RandomSource for OpenSimplexNoise {
+    fn rand(&self, time: f32) -> f32 {
+        self.sample(time * self.frequency, 0.0)
+    }
+}
+
+/// How sharp the edges of `Waveform::Square` are. Higher values are closer to a true square wave.
+const SQUARE_SHARPNESS: f32 = 4.0;
+
+/// The shape of an `Oscillator`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Waveform {
+    /// A sine wave.
+    #[default]
+    Sine,
+    /// A triangle wave, which moves at a constant speed and turns around sharply.
+    Triangle,
+    /// A square wave with softened edges, which snaps between the two extremes.
+    Square,
+}
+
+impl Waveform {
+    /// Samples the waveform at the given phase, in cycles. Returns a value between -1.0 and 1.0.
+    pub fn sample(&self, phase: f32) -> f32 {
+        match self {
+            Waveform::Sine => f32::sin(phase * TAU),
+            Waveform::Triangle => {
+                // Starts at zero and rises, like the sine wave.
+                let t = (phase + 0.25).rem_euclid(1.0);
+                1.0 - 4.0 * f32::abs(t - 0.5)
+            }
+            Waveform::Square => {
+                f32::tanh(SQUARE_SHARPNESS * f32::sin(phase * TAU)) / f32::tanh(SQUARE_SHARPNESS)
+            }
+        }
+    }
+}
+
+/// A periodic source, for shakes that should oscillate at a controllable frequency instead of moving randomly.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Oscillator {
+    /// The shape of the oscillation.
+    pub waveform: Waveform,
+    /// How many cycles are completed per second.
+    pub frequency: f32,
+    /// The offset of the oscillation, in cycles.
+    /// Give each axis a different phase (for example `0.25` apart) to get circular or elliptical motion.
+    pub phase: f32,
+}
+
+impl Oscillator {
+    /// Creates a new `Oscillator` with the given waveform and frequency, and no phase offset.
+    pub fn new(waveform: Waveform, frequency: f32) -> Self {
+        Self {
+            waveform,
+            frequency,
+            phase: 0.0,
+        }
+    }
+
+    /// Sets the phase of the oscillation, in cycles.
+    pub fn with_phase(mut self, phase: f32) -> Self {
+        self.phase = phase;
+        self
+    }
+}
+
+impl RandomSource for Oscillator {
+    fn rand(&self, time: f32) -> f32 {
+        self.waveform.sample(time * self.frequency + self.phase)
+    }
+}
+
+/// Adapts any 2D noise function of the `noise` crate, such as `noise::Fbm<noise::Perlin>`, into a `RandomSource`.
+/// Requires the `noise` feature.
+///
+/// The noise is sampled along a line through its 2D space: X advances with time, and Y stays at `offset.y`.
+/// Give each axis a different `offset.y` (or a differently seeded noise function), otherwise all axes will move
+/// in lockstep. The result is clamped between -1.0 and 1.0, as fractal noise can slightly exceed that range.
+#[cfg(feature = "noise")]
+#[derive(Clone, Debug)]
+pub struct NoiseFnSource<N> {
+    /// The noise function.
+    pub noise: N,
+    /// How many units of the noise are crossed per second.
+    /// Defaults to `15.0`.
+    pub frequency: f32,
+    /// Where in the noise the sampling starts.
+    /// Defaults to `Vec2::ZERO`.
+    pub offset: Vec2,
+}
+
+#[cfg(feature = "noise")]
+impl<N> NoiseFnSource<N> {
+    /// Samples the given noise function with the default frequency and no offset.
+    pub fn new(noise: N) -> Self {
+        Self {
+            noise,
+            frequency: DEFAULT_FREQUENCY,
+            offset: Vec2::ZERO,
+        }
+    }
+
+    /// Sets the frequency of the sampling.
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Sets where in the noise the sampling starts.
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+#[cfg(feature = "noise")]
+impl<N> RandomSource for NoiseFnSource<N>
+where
+    N: noise::NoiseFn<f64, 2> + Send + Sync,
+{
+    fn rand(&self, time: f32) -> f32 {
+        let x = f64::from(time) * f64::from(self.frequency) + f64::from(self.offset.x);
+        (self.noise.get([x, f64::from(self.offset.y)]) as f32).clamp(-1.0, 1.0)
+    }
+}
+
+/// The kinds of built-in noise that a `ShakeSource` can describe.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NoiseKind {
+    /// See `PerlinNoise`.
+    #[default]
+    Perlin,
+    /// See `OpenSimplexNoise`.
+    OpenSimplex,
+    /// See `ValueNoise`.
+    Value,
+}
+
+/// The source of randomness for one axis of a `Shake2d` or `Shake3d`.
+///
+/// Built-in noise and oscillators are described by their parameters, so they can be reflected, serialized
+/// and edited at runtime. Any other `RandomSource` can be used through `ShakeSource::Custom`,
+/// and any `ShakeSampler` through `ShakeSource::Sampler`, which are not reflected and are serialized as a placeholder.
+#[derive(Clone, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ShakeSource {
+    /// Built-in noise.
+    Noise {
+        /// The kind of noise.
+        kind: NoiseKind,
+        /// The seed of the noise. Sources with different seeds produce unrelated motion.
+        seed: u32,
+        /// The frequency of the noise, in samples per second.
+        frequency: f32,
+    },
+    /// A built-in `Oscillator`.
+    Oscillator {
+        /// The shape of the oscillation.
+        waveform: Waveform,
+        /// How many cycles are completed per second.
+        frequency: f32,
+        /// The offset of the oscillation, in cycles.
+        phase: f32,
+    },
+    /// A user-provided `RandomSource`.
+    /// Is serialized as a placeholder, and is replaced with a `NotRandom` when created through reflection or deserialized.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_placeholder",
+            deserialize_with = "deserialize_not_random"
+        )
+    )]
+    Custom(#[reflect(ignore, default = "not_random")] Arc<dyn RandomSource>),
+    /// A user-provided `ShakeSampler`. Clones of the source get their own copy of the sampler and its state.
+    /// Is serialized as a placeholder, and is replaced with a `NotRandom` when created through reflection or deserialized.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_placeholder",
+            deserialize_with = "deserialize_not_random_sampler"
+        )
+    )]
+    Sampler(#[reflect(ignore, default = "not_random_sampler")] Box<dyn ShakeSampler>),
+}
+
+/// The shared `NotRandom` source, so that it can be recognized by `ShakeSource::is_not_random`.
+static NOT_RANDOM: LazyLock<Arc<dyn RandomSource>> = LazyLock::new(|| Arc::new(NotRandom));
+
+fn not_random() -> Arc<dyn RandomSource> {
+    NOT_RANDOM.clone()
+}
+
+fn not_random_sampler() -> Box<dyn ShakeSampler> {
+    Box::new(ShakeSource::default())
+}
+
+/// Serializes a value that can't be serialized, such as a trait object, as a unit placeholder.
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_placeholder<T, S: Serializer>(
+    _value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_unit()
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_not_random<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Arc<dyn RandomSource>, D::Error> {
+    <()>::deserialize(deserializer)?;
+    Ok(not_random())
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_not_random_sampler<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Box<dyn ShakeSampler>, D::Error> {
+    <()>::deserialize(deserializer)?;
+    Ok(not_random_sampler())
+}
+
+impl ShakeSource {
+    /// Perlin noise with the given seed and the default frequency.
+    pub fn perlin(seed: u32) -> Self {
+        Self::noise(NoiseKind::Perlin, seed)
+    }
+
+    /// OpenSimplex noise with the given seed and the default frequency.
+    pub fn open_simplex(seed: u32) -> Self {
+        Self::noise(NoiseKind::OpenSimplex, seed)
+    }
+
+    /// Value noise with the given seed and the default frequency.
+    pub fn value(seed: u32) -> Self {
+        Self::noise(NoiseKind::Value, seed)
+    }
+
+    /// Noise of the given kind and seed, with the default frequency.
+    pub fn noise(kind: NoiseKind, seed: u32) -> Self {
+        ShakeSource::Noise {
+            kind,
+            seed,
+            frequency: DEFAULT_FREQUENCY,
+        }
+    }
+
+    /// An oscillator with the given waveform and frequency in cycles per second, and no phase offset.
+    pub fn oscillator(waveform: Waveform, frequency: f32) -> Self {
+        ShakeSource::Oscillator {
+            waveform,
+            frequency,
+            phase: 0.0,
+        }
+    }
+
+    /// Any other `RandomSource`.
+    pub fn custom(source: impl RandomSource + 'static) -> Self {
+        ShakeSource::Custom(Arc::new(source))
+    }
+
+    /// A `ShakeSampler`, which receives the context of every sample and can hold mutable state.
+    pub fn sampler(sampler: impl ShakeSampler + 'static) -> Self {
+        ShakeSource::Sampler(Box::new(sampler))
+    }
+
+    /// Whether this is the default `NotRandom` placeholder, which doesn't shake.
+    pub(crate) fn is_not_random(&self) -> bool {
+        match self {
+            // Compares the data pointers only, as vtable pointers aren't guaranteed to be unique.
+            ShakeSource::Custom(source) => {
+                std::ptr::addr_eq(Arc::as_ptr(source), Arc::as_ptr(&NOT_RANDOM))
+            }
+            _ => false,
+        }
+    }
+
+    /// Sets the frequency of built-in noise and oscillators. Does nothing for custom sources.
+    pub fn with_frequency(mut self, new_frequency: f32) -> Self {
+        if let ShakeSource::Noise { frequency, .. } | ShakeSource::Oscillator { frequency, .. } =
+            &mut self
+        {
+            *frequency = new_frequency;
+        }
+        self
+    }
+
+    /// Sets the phase of an oscillator, in cycles. Does nothing for other sources.
+    pub fn with_phase(mut self, new_phase: f32) -> Self {
+        if let ShakeSource::Oscillator { phase, .. } = &mut self {
+            *phase = new_phase;
+        }
+        self
+    }
+}
+
+impl Default for ShakeSource {
+    fn default() -> Self {
+        ShakeSource::Custom(not_random())
+    }
+}
+
+impl RandomSource for ShakeSource {
+    fn rand(&self, time: f32) -> f32 {
+        match self {
+            ShakeSource::Noise {
+                kind,
+                seed,
+                frequency,
+            } => {
+                let x = time * frequency;
+                match kind {
+                    NoiseKind::Perlin => PerlinNoise::new(*seed).sample(x),
+                    NoiseKind::OpenSimplex => OpenSimplexNoise::new(*seed).sample(x, 0.0),
+                    NoiseKind::Value => ValueNoise::new(*seed).sample(x),
+                }
+            }
+            ShakeSource::Oscillator {
+                waveform,
+                frequency,
+                phase,
+            } => Oscillator::new(*waveform, *frequency)
+                .with_phase(*phase)
+                .rand(time),
+            ShakeSource::Custom(source) => source.rand(time),
+            // Samples a copy, as the state of the sampler can't be advanced through a shared reference.
+            ShakeSource::Sampler(sampler) => {
+                sampler.clone_box().sample(&SampleContext::at_time(time))
+            }
+        }
+    }
+}
+
+/// Samples `RandomSource`s with the time of the context, and `ShakeSampler`s with the full context.
+impl ShakeSampler for ShakeSource {
+    fn sample(&mut self, context: &SampleContext) -> f32 {
+        match self {
+            ShakeSource::Sampler(sampler) => sampler.sample(context),
+            _ => self.rand(context.time),
+        }
+    }
+}
+
+impl fmt::Debug for ShakeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShakeSource::Noise {
+                kind,
+                seed,
+                frequency,
+            } => f
+                .debug_struct("Noise")
+                .field("kind", kind)
+                .field("seed", seed)
+                .field("frequency", frequency)
+                .finish(),
+            ShakeSource::Oscillator {
+                waveform,
+                frequency,
+                phase,
+            } => f
+                .debug_struct("Oscillator")
+                .field("waveform", waveform)
+                .field("frequency", frequency)
+                .field("phase", phase)
+                .finish(),
+            ShakeSource::Custom(_) => f.debug_tuple("Custom").finish_non_exhaustive(),
+            ShakeSource::Sampler(_) => f.debug_tuple("Sampler").finish_non_exhaustive(),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Applying shakes through the camera's sub view instead of its `Transform`, for `ShakeMode::SubView`.
+
+use bevy::{
+    prelude::{Camera, Mut, Query, Vec2, Vec3},
+    render::camera::SubCameraView,
+};
+
+use crate::{shake::ShakeCore, AppliedShake, ShakeMode};
+
+/// Offsets the rendered image of a camera by a translation in logical pixels,
+/// by rendering a full size sub view of it at an offset.
+fn set_sub_view_offset(camera: &mut Mut<Camera>, translation: Vec3) {
+    if translation.x == 0.0 && translation.y == 0.0 {
+        // Only write to the camera when needed, as changing it recomputes the projection.
+        if camera.sub_camera_view.is_some() {
+            camera.sub_camera_view = None;
+        }
+        return;
+    }
+    let (Some(size), Some(scale_factor)) = (
+        camera.physical_viewport_size(),
+        camera.target_scaling_factor(),
+    ) else {
+        // The viewport size isn't known until the camera has been updated once.
+        return;
+    };
+    camera.sub_camera_view = Some(SubCameraView {
+        full_size: size,
+        // The Y axis of the sub view points down.
+        offset: Vec2::new(translation.x, -translation.y) * scale_factor,
+        size,
+    });
+}
+
+/// Offsets the sub view of cameras with a `ShakeMode::SubView` shake.
+/// Runs after the shakes are applied, and before the camera's matrices are updated.
+pub(crate) fn apply_sub_view_shake<S: ShakeCore>(
+    mut query: Query<(&mut Camera, &AppliedShake, &S)>,
+) {
+    for (mut camera, applied, shake_settings) in query.iter_mut() {
+        if shake_settings.mode() == ShakeMode::SubView {
+            set_sub_view_offset(&mut camera, applied.translation);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Utilities for testing shakes without a window or a renderer, for example in CI.
+//!
+//! ```ignore
+//! let mut app = testing::app();
+//! let camera = app
+//!     .world_mut()
+//!     .spawn(Shake2d {
+//!         trauma: 1.0,
+//!         random_sources: std::array::from_fn(|_| ScriptedSource::constant(1.0).into()),
+//!         ..default()
+//!     })
+//!     .id();
+//! testing::run_frames(&mut app, 10);
+//! ```
+
+use std::time::Duration;
+
+use bevy::{
+    prelude::{App, MinimalPlugins, TransformPlugin},
+    time::TimeUpdateStrategy,
+};
+
+use crate::{CameraShakePlugin, RandomSource, ShakeSource};
+
+/// The time step of the apps created by `app`: 60 frames per second.
+pub const TIMESTEP: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// Creates an app with `MinimalPlugins`, the `TransformPlugin` and the `CameraShakePlugin`,
+/// whose `Time` advances by exactly `TIMESTEP` every update.
+pub fn app() -> App {
+    app_with_timestep(TIMESTEP)
+}
+
+/// Creates an app like `app`, whose `Time` advances by exactly `timestep` every update.
+pub fn app_with_timestep(timestep: Duration) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        CameraShakePlugin::default(),
+    ))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+    // The first update starts the clock, so `Time` advances by `timestep` on every update after it.
+    app.update();
+    app
+}
+
+/// Runs `frames` updates of the app.
+pub fn run_frames(app: &mut App, frames: u32) {
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+/// A `RandomSource` that plays back a list of values, for predictable shakes in tests.
+///
+/// Every value is held for `interval` seconds, after which the next one is returned.
+/// The last value is held forever.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptedSource {
+    /// The values to return, in order.
+    pub values: Vec<f32>,
+    /// The number of seconds each value is held for.
+    pub interval: f32,
+}
+
+impl ScriptedSource {
+    /// Plays back `values`, holding each of them for `interval` seconds.
+    pub fn new(values: impl IntoIterator<Item = f32>, interval: f32) -> Self {
+        Self {
+            values: values.into_iter().collect(),
+            interval,
+        }
+    }
+
+    /// Always returns `value`.
+    pub fn constant(value: f32) -> Self {
+        Self::new([value], f32::INFINITY)
+    }
+}
+
+impl RandomSource for ScriptedSource {
+    fn rand(&self, time: f32) -> f32 {
+        let index = if self.interval > 0.0 {
+            (time / self.interval).max(0.0) as usize
+        } else {
+            0
+        };
+        self.values
+            .get(index)
+            .or(self.values.last())
+            .copied()
+            .unwrap_or(0.0)
+    }
+}
+
+impl From<ScriptedSource> for ShakeSource {
+    fn from(source: ScriptedSource) -> Self {
+        ShakeSource::custom(source)
+    }
+}
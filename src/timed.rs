@@ -0,0 +1,161 @@
+//! Shakes that follow an attack/decay/sustain/release envelope over a fixed duration.
+
+use bevy::prelude::{
+    Commands, Component, Entity, Query, Reflect, ReflectComponent, ReflectDefault, With,
+};
+#[cfg(feature = "serde")]
+use bevy::prelude::{ReflectDeserialize, ReflectSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::shake::ShakeCore;
+
+/// What happens to a `TimedShake` when its envelope finishes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TimedShakeEnd {
+    /// Removes the `TimedShake`. The trauma of the shake is left untouched.
+    #[default]
+    Remove,
+    /// Despawns the entity, along with its children.
+    Despawn,
+}
+
+/// Plays an attack/decay/sustain/release envelope on a `Shake2d` or `Shake3d`, for shakes that should ramp in,
+/// such as an approaching train.
+///
+/// While it plays, the envelope is the amplitude of the shake's random sources, independently of its trauma,
+/// so the shake doesn't need any trauma and the envelope isn't cut short by trauma decay.
+/// Trauma added during the envelope still shakes harder when it is higher than the envelope.
+/// The `ShakeLayers` and `ShakeKick` of the shake are not affected.
+/// When the envelope finishes, the `TimedShake` removes itself or despawns the entity, depending on `end`.
+///
+/// ```text
+///  1.0 |   /\
+///      |  /  \______________
+///      | /                  \
+///  0.0 |/                    \
+///      | attack | decay | sustain | release
+/// ```
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    reflect(Serialize, Deserialize)
+)]
+pub struct TimedShake {
+    /// The number of seconds it takes to ramp up from nothing to the full amplitude.
+    /// Defaults to `0.5`.
+    pub attack: f32,
+    /// The number of seconds it takes to go down from the full amplitude to `sustain_level`.
+    /// Defaults to `0.25`.
+    pub decay: f32,
+    /// The amplitude held during the sustain, relative to the full amplitude.
+    /// Defaults to `0.6`.
+    pub sustain_level: f32,
+    /// The number of seconds the amplitude is held at `sustain_level`.
+    /// Defaults to `1.0`.
+    pub sustain: f32,
+    /// The number of seconds it takes to go down from `sustain_level` to nothing.
+    /// Defaults to `1.0`.
+    pub release: f32,
+    /// The number of seconds the envelope has been playing for.
+    /// Defaults to `0.0`.
+    pub elapsed: f32,
+    /// What happens when the envelope finishes.
+    /// Defaults to `TimedShakeEnd::Remove`.
+    pub end: TimedShakeEnd,
+}
+
+impl Default for TimedShake {
+    fn default() -> Self {
+        Self {
+            attack: 0.5,
+            decay: 0.25,
+            sustain_level: 0.6,
+            sustain: 1.0,
+            release: 1.0,
+            elapsed: 0.0,
+            end: TimedShakeEnd::Remove,
+        }
+    }
+}
+
+impl TimedShake {
+    /// Creates a `TimedShake` with the given attack, decay, sustain level, sustain and release.
+    pub fn new(attack: f32, decay: f32, sustain_level: f32, sustain: f32, release: f32) -> Self {
+        Self {
+            attack,
+            decay,
+            sustain_level,
+            sustain,
+            release,
+            ..Default::default()
+        }
+    }
+
+    /// Sets what happens when the envelope finishes.
+    pub fn with_end(mut self, end: TimedShakeEnd) -> Self {
+        self.end = end;
+        self
+    }
+
+    /// The total number of seconds the envelope plays for.
+    pub fn duration(&self) -> f32 {
+        self.attack + self.decay + self.sustain + self.release
+    }
+
+    /// Whether the envelope has finished playing.
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration()
+    }
+
+    /// The value of the envelope at the current time, between `0.0` and `1.0`.
+    pub fn envelope(&self) -> f32 {
+        let mut t = self.elapsed.max(0.0);
+        if t < self.attack {
+            return t / self.attack;
+        }
+        t -= self.attack;
+        if t < self.decay {
+            return 1.0 - (1.0 - self.sustain_level) * t / self.decay;
+        }
+        t -= self.decay;
+        if t < self.sustain {
+            return self.sustain_level;
+        }
+        t -= self.sustain;
+        if t < self.release {
+            return self.sustain_level * (1.0 - t / self.release);
+        }
+        0.0
+    }
+
+    /// Advances the envelope by `delta_secs` seconds, and returns its new value.
+    pub(crate) fn tick(&mut self, delta_secs: f32) -> f32 {
+        self.elapsed += delta_secs;
+        self.envelope()
+    }
+}
+
+/// Removes finished `TimedShake`s, or despawns their entities.
+pub(crate) fn finish_timed_shakes<S: ShakeCore>(
+    mut commands: Commands,
+    query: Query<(Entity, &TimedShake), With<S>>,
+) {
+    for (entity, timed_shake) in query.iter() {
+        if !timed_shake.is_finished() {
+            continue;
+        }
+        match timed_shake.end {
+            TimedShakeEnd::Remove => {
+                commands.entity(entity).remove::<TimedShake>();
+            }
+            TimedShakeEnd::Despawn => {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
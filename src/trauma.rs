@@ -0,0 +1,279 @@
+//! Adding trauma to shakes, and how it decays over time.
+
+use std::{fmt, ops::DerefMut, sync::Arc};
+
+use bevy::{
+    ecs::system::EntityCommands,
+    log::warn,
+    math::{
+        curve::{ConstantCurve, Interval},
+        Curve,
+    },
+    prelude::{
+        Entity, Event, EventReader, Mut, Query, Reflect, ReflectDefault, Trigger, Vec3, Without,
+    },
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[cfg(feature = "serde")]
+use crate::sources::serialize_placeholder;
+use crate::{shake::ShakeCore, Shake2d, Shake3d, ShakeDirection};
+
+/// Exponentially decaying trauma below this value is snapped to zero, as it would otherwise never reach it.
+const MIN_EXPONENTIAL_TRAUMA: f32 = 0.001;
+
+/// How the trauma of a shake decreases over time.
+#[derive(Clone, Reflect)]
+#[reflect(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TraumaDecay {
+    /// Decreases trauma by a fixed amount per second.
+    /// If set to 1, there will be no trauma after 1 second. If set to 0, trauma will not decrease over time.
+    /// If set below 0, trauma will *increase* over time, and if set above 1, trauma will decrease very quickly.
+    Linear(f32),
+    /// Halves the trauma every `half_life` seconds.
+    /// Big hits fade out with a long, rumbling tail.
+    Exponential {
+        /// The number of seconds it takes for the trauma to halve.
+        half_life: f32,
+    },
+    /// Decreases trauma by the amount per second returned by the curve, sampled at the current trauma.
+    /// The curve is sampled between `0.0` and `1.0`, and is clamped to its domain.
+    /// For example, a curve that returns large values for low trauma gives a snappy cutoff.
+    /// The curve is not reflected and is serialized as a placeholder. It is replaced with a curve that never decays
+    /// when created through reflection or deserialized.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "serialize_placeholder",
+            deserialize_with = "deserialize_no_decay_curve"
+        )
+    )]
+    Curve(#[reflect(ignore, default = "no_decay_curve")] Arc<dyn Curve<f32> + Send + Sync>),
+}
+
+fn no_decay_curve() -> Arc<dyn Curve<f32> + Send + Sync> {
+    Arc::new(ConstantCurve::new(Interval::UNIT, 0.0))
+}
+
+#[cfg(feature = "serde")]
+fn deserialize_no_decay_curve<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Arc<dyn Curve<f32> + Send + Sync>, D::Error> {
+    <()>::deserialize(deserializer)?;
+    Ok(no_decay_curve())
+}
+
+impl TraumaDecay {
+    /// Returns the trauma after `delta_secs` seconds of decay.
+    pub fn decay(&self, trauma: f32, delta_secs: f32) -> f32 {
+        match self {
+            TraumaDecay::Linear(decay) => f32::max(trauma - decay * delta_secs, 0.0),
+            TraumaDecay::Exponential { half_life } => {
+                if *half_life <= 0.0 {
+                    return 0.0;
+                }
+                let trauma = trauma * f32::powf(0.5, delta_secs / half_life);
+                if trauma < MIN_EXPONENTIAL_TRAUMA {
+                    0.0
+                } else {
+                    trauma
+                }
+            }
+            TraumaDecay::Curve(curve) => {
+                f32::max(trauma - curve.sample_clamped(trauma) * delta_secs, 0.0)
+            }
+        }
+    }
+}
+
+impl Default for TraumaDecay {
+    fn default() -> Self {
+        TraumaDecay::Linear(0.8)
+    }
+}
+
+impl fmt::Debug for TraumaDecay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraumaDecay::Linear(decay) => f.debug_tuple("Linear").field(decay).finish(),
+            TraumaDecay::Exponential { half_life } => f
+                .debug_struct("Exponential")
+                .field("half_life", half_life)
+                .finish(),
+            TraumaDecay::Curve(_) => f.debug_tuple("Curve").finish_non_exhaustive(),
+        }
+    }
+}
+
+/// How an `AddTrauma` changes the trauma of a shake.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TraumaMode {
+    /// Adds the amount to the current trauma.
+    #[default]
+    Add,
+    /// Sets the trauma to the amount, unless the current trauma is already higher.
+    /// Useful for continuous sources of shake that shouldn't stack, such as standing next to an engine.
+    AtLeast,
+}
+
+/// Adds trauma to the `Shake2d` or `Shake3d` on the `target` entity.
+/// The resulting trauma is always clamped between `0.0` and `1.0`.
+///
+/// Can either be sent as a buffered event with an `EventWriter<AddTrauma>`,
+/// or triggered immediately with `Commands::trigger`.
+/// See also `ShakeCommandsExt`, which does the latter for you.
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub struct AddTrauma {
+    /// The entity with the `Shake2d` or `Shake3d` component.
+    pub target: Entity,
+    /// The amount of trauma to add.
+    pub amount: f32,
+    /// How the amount is combined with the current trauma.
+    pub mode: TraumaMode,
+    /// The world space direction of the impact, used by shakes with a `ShakeDirection`.
+    pub direction: Option<Vec3>,
+}
+
+impl AddTrauma {
+    /// Adds `amount` to the trauma of `target`.
+    pub fn new(target: Entity, amount: f32) -> Self {
+        Self {
+            target,
+            amount,
+            mode: TraumaMode::Add,
+            direction: None,
+        }
+    }
+
+    /// Raises the trauma of `target` to at least `amount`.
+    pub fn at_least(target: Entity, amount: f32) -> Self {
+        Self {
+            target,
+            amount,
+            mode: TraumaMode::AtLeast,
+            direction: None,
+        }
+    }
+
+    /// Sets the world space direction of the impact, used by shakes with a `ShakeDirection`.
+    pub fn with_direction(mut self, direction: Vec3) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+}
+
+/// Combines `amount` with `trauma` according to `mode`, clamping the result between `0.0` and `1.0`.
+pub(crate) fn apply_trauma(trauma: &mut f32, amount: f32, mode: TraumaMode) {
+    let new_trauma = match mode {
+        TraumaMode::Add => *trauma + amount,
+        TraumaMode::AtLeast => f32::max(*trauma, amount),
+    };
+    *trauma = new_trauma.clamp(0.0, 1.0);
+}
+
+/// Adds trauma to a shake, and orients its `ShakeDirection` by the direction of the impact, if it has one.
+/// The trauma is only written when it changes, so that adding no trauma doesn't mark the shake as changed.
+pub(crate) fn add_trauma_to_shake<S: ShakeCore>(
+    mut shake: impl DerefMut<Target = S>,
+    shake_direction: Option<Mut<ShakeDirection>>,
+    amount: f32,
+    mode: TraumaMode,
+    direction: Option<Vec3>,
+) {
+    if let (Some(mut shake_direction), Some(direction)) = (shake_direction, direction) {
+        shake_direction.add(direction, amount, shake.trauma());
+    }
+    let mut trauma = shake.trauma();
+    apply_trauma(&mut trauma, amount, mode);
+    if trauma != shake.trauma() {
+        *shake.trauma_mut() = trauma;
+    }
+}
+
+/// The components used to add trauma to a shake.
+type TraumaTarget<S> = (&'static mut S, Option<&'static mut ShakeDirection>);
+
+fn add_trauma_to_target(
+    event: &AddTrauma,
+    shakes_2d: &mut Query<TraumaTarget<Shake2d>>,
+    shakes_3d: &mut Query<TraumaTarget<Shake3d>, Without<Shake2d>>,
+) {
+    if let Ok((shake, shake_direction)) = shakes_2d.get_mut(event.target) {
+        add_trauma_to_shake(
+            shake,
+            shake_direction,
+            event.amount,
+            event.mode,
+            event.direction,
+        );
+    } else if let Ok((shake, shake_direction)) = shakes_3d.get_mut(event.target) {
+        add_trauma_to_shake(
+            shake,
+            shake_direction,
+            event.amount,
+            event.mode,
+            event.direction,
+        );
+    } else {
+        warn!(
+            "AddTrauma targeted {}, which has no Shake2d or Shake3d component.",
+            event.target
+        );
+    }
+}
+
+pub(crate) fn handle_add_trauma_events(
+    mut events: EventReader<AddTrauma>,
+    mut shakes_2d: Query<TraumaTarget<Shake2d>>,
+    mut shakes_3d: Query<TraumaTarget<Shake3d>, Without<Shake2d>>,
+) {
+    for event in events.read() {
+        add_trauma_to_target(event, &mut shakes_2d, &mut shakes_3d);
+    }
+}
+
+pub(crate) fn on_add_trauma(
+    trigger: Trigger<AddTrauma>,
+    mut shakes_2d: Query<TraumaTarget<Shake2d>>,
+    mut shakes_3d: Query<TraumaTarget<Shake3d>, Without<Shake2d>>,
+) {
+    add_trauma_to_target(trigger.event(), &mut shakes_2d, &mut shakes_3d);
+}
+
+/// Adds trauma to a shake through `EntityCommands`.
+///
+/// ```ignore
+/// commands.entity(camera).add_trauma(0.5);
+/// ```
+pub trait ShakeCommandsExt {
+    /// Adds `amount` to the trauma of this entity's `Shake2d` or `Shake3d`.
+    fn add_trauma(&mut self, amount: f32) -> &mut Self;
+    /// Raises the trauma of this entity's `Shake2d` or `Shake3d` to at least `amount`.
+    fn set_trauma_at_least(&mut self, amount: f32) -> &mut Self;
+    /// Adds `amount` to the trauma of this entity's `Shake2d` or `Shake3d`,
+    /// from an impact in the given world space direction.
+    fn add_directional_trauma(&mut self, amount: f32, direction: Vec3) -> &mut Self;
+}
+
+impl ShakeCommandsExt for EntityCommands<'_> {
+    fn add_trauma(&mut self, amount: f32) -> &mut Self {
+        let target = self.id();
+        self.commands().trigger(AddTrauma::new(target, amount));
+        self
+    }
+
+    fn set_trauma_at_least(&mut self, amount: f32) -> &mut Self {
+        let target = self.id();
+        self.commands().trigger(AddTrauma::at_least(target, amount));
+        self
+    }
+
+    fn add_directional_trauma(&mut self, amount: f32, direction: Vec3) -> &mut Self {
+        let target = self.id();
+        self.commands()
+            .trigger(AddTrauma::new(target, amount).with_direction(direction));
+        self
+    }
+}
@@ -0,0 +1,258 @@
+//! Checks the configuration of shakes, and reports every problem once.
+
+use std::fmt;
+
+use bevy::{
+    log::warn,
+    platform::collections::HashMap,
+    prelude::{
+        Changed, Entity, Event, EventWriter, Has, Local, Or, Query, RemovedComponents, Transform,
+        Vec2, Vec3,
+    },
+};
+
+use crate::{shake::ShakeCore, Shake2d, Shake3d, ShakeNoise, ShakeSource, TraumaDecay};
+
+/// A problem with the configuration of a `Shake2d` or `Shake3d`.
+///
+/// Shakes are validated by the `CameraShakePlugin` when they are added or changed,
+/// and every problem is logged as a warning once, until it is fixed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShakeProblem {
+    /// An axis still uses the default `NotRandom` source, which doesn't shake.
+    MissingRandomSource {
+        /// The index of the axis in `random_sources`.
+        axis: usize,
+    },
+    /// A parameter is negative, NaN or infinite.
+    InvalidParameter {
+        /// The name of the parameter.
+        name: &'static str,
+        /// The value of the parameter.
+        value: f32,
+    },
+    /// The trauma is above `1.0`, which shakes further than the maximum offset and rotation.
+    TraumaAboveOne(f32),
+    /// The entity has no `Transform`, so the shake is never applied.
+    MissingTransform,
+}
+
+/// Sent by the `CameraShakePlugin` every time a problem with a shake is logged.
+/// Like the warning, it is only sent once per problem, until the problem is fixed.
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct ShakeProblemReported {
+    /// The entity of the shake.
+    pub entity: Entity,
+    /// The problem.
+    pub problem: ShakeProblem,
+}
+
+impl fmt::Display for ShakeProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShakeProblem::MissingRandomSource { axis } => write!(
+                f,
+                "random_sources[{axis}] is not set, so that axis doesn't shake"
+            ),
+            ShakeProblem::InvalidParameter { name, value } => {
+                write!(f, "{name} is {value}, which is not a valid value")
+            }
+            ShakeProblem::TraumaAboveOne(trauma) => {
+                write!(f, "trauma is {trauma}, which is above 1.0")
+            }
+            ShakeProblem::MissingTransform => {
+                write!(
+                    f,
+                    "the entity has no Transform, so the shake is never applied"
+                )
+            }
+        }
+    }
+}
+
+impl ShakeProblem {
+    /// Whether two problems are about the same thing, regardless of the offending value,
+    /// so that a problem with a value that changes every frame (such as a NaN trauma) is only reported once.
+    fn is_same_as(&self, other: &ShakeProblem) -> bool {
+        match (self, other) {
+            (
+                ShakeProblem::MissingRandomSource { axis },
+                ShakeProblem::MissingRandomSource { axis: other_axis },
+            ) => axis == other_axis,
+            (
+                ShakeProblem::InvalidParameter { name, .. },
+                ShakeProblem::InvalidParameter {
+                    name: other_name, ..
+                },
+            ) => name == other_name,
+            (ShakeProblem::TraumaAboveOne(_), ShakeProblem::TraumaAboveOne(_))
+            | (ShakeProblem::MissingTransform, ShakeProblem::MissingTransform) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Collects the problems with a parameter that should be finite and not negative.
+fn check_non_negative(problems: &mut Vec<ShakeProblem>, name: &'static str, value: f32) {
+    if !value.is_finite() || value < 0.0 {
+        problems.push(ShakeProblem::InvalidParameter { name, value });
+    }
+}
+
+fn check_vec2(problems: &mut Vec<ShakeProblem>, names: [&'static str; 2], value: Vec2) {
+    for (name, value) in names.into_iter().zip(value.to_array()) {
+        check_non_negative(problems, name, value);
+    }
+}
+
+fn check_vec3(problems: &mut Vec<ShakeProblem>, names: [&'static str; 3], value: Vec3) {
+    for (name, value) in names.into_iter().zip(value.to_array()) {
+        check_non_negative(problems, name, value);
+    }
+}
+
+fn check_trauma(problems: &mut Vec<ShakeProblem>, trauma: f32, trauma_power: f32) {
+    if trauma > 1.0 {
+        problems.push(ShakeProblem::TraumaAboveOne(trauma));
+    } else {
+        check_non_negative(problems, "trauma", trauma);
+    }
+    // A power of zero keeps shaking at full strength without trauma.
+    if !trauma_power.is_finite() || trauma_power <= 0.0 {
+        problems.push(ShakeProblem::InvalidParameter {
+            name: "trauma_power",
+            value: trauma_power,
+        });
+    }
+}
+
+fn check_decay(problems: &mut Vec<ShakeProblem>, decay: &TraumaDecay) {
+    match decay {
+        // A negative linear decay is allowed, and increases the trauma over time.
+        TraumaDecay::Linear(decay) if !decay.is_finite() => {
+            problems.push(ShakeProblem::InvalidParameter {
+                name: "decay",
+                value: *decay,
+            });
+        }
+        TraumaDecay::Exponential { half_life } => {
+            check_non_negative(problems, "decay.half_life", *half_life);
+        }
+        _ => {}
+    }
+}
+
+fn check_sources(problems: &mut Vec<ShakeProblem>, sources: &[ShakeSource]) {
+    for (axis, source) in sources.iter().enumerate() {
+        if source.is_not_random() {
+            problems.push(ShakeProblem::MissingRandomSource { axis });
+        }
+        if let ShakeSource::Noise { frequency, .. } | ShakeSource::Oscillator { frequency, .. } =
+            source
+        {
+            check_non_negative(problems, "random_sources.frequency", *frequency);
+        }
+    }
+}
+
+impl Shake3d {
+    /// Returns the problems with the configuration of this shake.
+    pub fn validate(&self) -> Vec<ShakeProblem> {
+        let mut problems = Vec::new();
+        check_vec3(
+            &mut problems,
+            ["max_offset.x", "max_offset.y", "max_offset.z"],
+            self.max_offset,
+        );
+        check_vec3(
+            &mut problems,
+            [
+                "max_yaw_pitch_roll.x",
+                "max_yaw_pitch_roll.y",
+                "max_yaw_pitch_roll.z",
+            ],
+            self.max_yaw_pitch_roll,
+        );
+        check_trauma(&mut problems, self.trauma, self.trauma_power);
+        check_decay(&mut problems, &self.decay);
+        check_sources(&mut problems, &self.random_sources);
+        problems
+    }
+}
+
+impl Shake2d {
+    /// Returns the problems with the configuration of this shake.
+    pub fn validate(&self) -> Vec<ShakeProblem> {
+        let mut problems = Vec::new();
+        check_vec2(
+            &mut problems,
+            ["max_offset.x", "max_offset.y"],
+            self.max_offset,
+        );
+        check_non_negative(&mut problems, "max_roll", self.max_roll);
+        check_trauma(&mut problems, self.trauma, self.trauma_power);
+        check_decay(&mut problems, &self.decay);
+        check_sources(&mut problems, &self.random_sources);
+        problems
+    }
+}
+
+/// Logs the problems of a shake that weren't reported for it yet, and remembers them.
+fn report(
+    reported: &mut HashMap<Entity, Vec<ShakeProblem>>,
+    events: &mut EventWriter<ShakeProblemReported>,
+    entity: Entity,
+    component: &str,
+    mut problems: Vec<ShakeProblem>,
+    has_transform: bool,
+) {
+    if !has_transform {
+        problems.push(ShakeProblem::MissingTransform);
+    }
+    let previous = reported.remove(&entity).unwrap_or_default();
+    for problem in problems.iter() {
+        if !previous.iter().any(|previous| previous.is_same_as(problem)) {
+            warn!("{component} on {entity}: {problem}.");
+            events.write(ShakeProblemReported {
+                entity,
+                problem: problem.clone(),
+            });
+        }
+    }
+    if !problems.is_empty() {
+        reported.insert(entity, problems);
+    }
+}
+
+/// The shakes to validate, and whether they have what they need to be applied.
+type ShakeChecks<S> = (Entity, &'static S, Has<Transform>, Has<ShakeNoise>);
+
+/// Shakes are validated again when they change, or when their `ShakeNoise` does.
+type ShakeChanged<S> = Or<(Changed<S>, Changed<ShakeNoise>)>;
+
+/// Validates shakes when they are added or changed, and reports every problem once, until it is fixed.
+pub(crate) fn validate_shakes<S: ShakeCore>(
+    mut reported: Local<HashMap<Entity, Vec<ShakeProblem>>>,
+    shakes: Query<ShakeChecks<S>, ShakeChanged<S>>,
+    mut removed: RemovedComponents<S>,
+    mut events: EventWriter<ShakeProblemReported>,
+) {
+    for entity in removed.read() {
+        reported.remove(&entity);
+    }
+    for (entity, shake, has_transform, has_noise) in shakes.iter() {
+        let mut problems = shake.problems();
+        if has_noise {
+            // The random sources are not used.
+            problems.retain(|problem| !matches!(problem, ShakeProblem::MissingRandomSource { .. }));
+        }
+        report(
+            &mut reported,
+            &mut events,
+            entity,
+            S::NAME,
+            problems,
+            has_transform,
+        );
+    }
+}
@@ -0,0 +1,548 @@
+use bevy::prelude::*;
+use bevy_camera_shake::{
+    testing::{self, ScriptedSource},
+    Falloff, SampleContext, Shake2d, Shake3d, ShakeClock, ShakeDirection, ShakeEmitter,
+    ShakeImpulse, ShakeLayer, ShakeLayers, ShakeMode, ShakeSampler, ShakeSnapshot, ShakeSource,
+    TimedShake, TraumaDecay,
+};
+
+/// Random sources that always return `value`.
+fn constant_sources<const N: usize>(value: f32) -> [ShakeSource; N] {
+    std::array::from_fn(|_| ScriptedSource::constant(value).into())
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 1e-4,
+        "expected {expected}, got {actual}"
+    );
+}
+
+fn spawn_shake_2d(app: &mut App, shake: Shake2d) -> Entity {
+    app.world_mut().spawn((Transform::default(), shake)).id()
+}
+
+fn trauma_2d(app: &App, entity: Entity) -> f32 {
+    app.world().get::<Shake2d>(entity).unwrap().trauma
+}
+
+fn transform(app: &App, entity: Entity) -> Transform {
+    *app.world().get::<Transform>(entity).unwrap()
+}
+
+#[test]
+fn linear_decay_reduces_trauma_per_second() {
+    let mut app = testing::app();
+    let shake = spawn_shake_2d(
+        &mut app,
+        Shake2d {
+            trauma: 1.0,
+            decay: TraumaDecay::Linear(0.5),
+            random_sources: constant_sources(1.0),
+            ..default()
+        },
+    );
+
+    testing::run_frames(&mut app, 30);
+
+    assert_close(trauma_2d(&app, shake), 0.75);
+}
+
+#[test]
+fn exponential_decay_halves_trauma_every_half_life() {
+    let mut app = testing::app();
+    let shake = spawn_shake_2d(
+        &mut app,
+        Shake2d {
+            trauma: 0.8,
+            decay: TraumaDecay::Exponential { half_life: 0.5 },
+            random_sources: constant_sources(1.0),
+            ..default()
+        },
+    );
+
+    testing::run_frames(&mut app, 30);
+    assert_close(trauma_2d(&app, shake), 0.4);
+
+    testing::run_frames(&mut app, 30);
+    assert_close(trauma_2d(&app, shake), 0.2);
+}
+
+#[test]
+fn trauma_power_scales_offset() {
+    let mut app = testing::app();
+    let squared = spawn_shake_2d(
+        &mut app,
+        Shake2d {
+            max_offset: Vec2::new(10.0, 20.0),
+            trauma: 0.5,
+            trauma_power: 2.0,
+            decay: TraumaDecay::Linear(0.0),
+            random_sources: constant_sources(1.0),
+            ..default()
+        },
+    );
+    let cubed = spawn_shake_2d(
+        &mut app,
+        Shake2d {
+            max_offset: Vec2::new(10.0, 20.0),
+            trauma: 0.5,
+            trauma_power: 3.0,
+            decay: TraumaDecay::Linear(0.0),
+            random_sources: constant_sources(1.0),
+            ..default()
+        },
+    );
+
+    app.update();
+
+    let squared = transform(&app, squared).translation;
+    assert_close(squared.x, 2.5);
+    assert_close(squared.y, 5.0);
+    let cubed = transform(&app, cubed).translation;
+    assert_close(cubed.x, 1.25);
+    assert_close(cubed.y, 2.5);
+}
+
+#[test]
+fn zero_trauma_resets_replace_shake() {
+    let mut app = testing::app();
+    let shake = spawn_shake_2d(
+        &mut app,
+        Shake2d {
+            trauma: 0.1,
+            decay: TraumaDecay::Linear(1.0),
+            random_sources: constant_sources(1.0),
+            mode: ShakeMode::Replace,
+            ..default()
+        },
+    );
+
+    app.update();
+    assert_ne!(transform(&app, shake).translation, Vec3::ZERO);
+
+    testing::run_frames(&mut app, 10);
+    assert_eq!(trauma_2d(&app, shake), 0.0);
+    assert_eq!(transform(&app, shake).translation, Vec3::ZERO);
+    assert_eq!(transform(&app, shake).rotation, Quat::IDENTITY);
+}
+
+#[test]
+fn zero_trauma_restores_additive_shake() {
+    let mut app = testing::app();
+    let start = Transform::from_xyz(5.0, -3.0, 0.0);
+    let shake = app
+        .world_mut()
+        .spawn((
+            start,
+            Shake2d {
+                trauma: 0.1,
+                decay: TraumaDecay::Linear(1.0),
+                random_sources: constant_sources(1.0),
+                mode: ShakeMode::Additive,
+                ..default()
+            },
+        ))
+        .id();
+
+    app.update();
+    assert_ne!(transform(&app, shake).translation, start.translation);
+
+    testing::run_frames(&mut app, 10);
+    assert_eq!(trauma_2d(&app, shake), 0.0);
+    assert!(transform(&app, shake)
+        .translation
+        .abs_diff_eq(start.translation, 1e-5));
+    assert!(transform(&app, shake)
+        .rotation
+        .abs_diff_eq(Quat::IDENTITY, 1e-5));
+}
+
+#[test]
+fn shake_2d_only_rolls() {
+    let mut app = testing::app();
+    let shake = spawn_shake_2d(
+        &mut app,
+        Shake2d {
+            max_offset: Vec2::ZERO,
+            max_roll: 0.1,
+            trauma: 1.0,
+            trauma_power: 1.0,
+            decay: TraumaDecay::Linear(0.0),
+            random_sources: [
+                ScriptedSource::constant(0.0).into(),
+                ScriptedSource::constant(0.0).into(),
+                ScriptedSource::constant(0.5).into(),
+            ],
+            ..default()
+        },
+    );
+
+    app.update();
+
+    let transform = transform(&app, shake);
+    let (yaw, pitch, roll) = transform.rotation.to_euler(EulerRot::YXZ);
+    assert_close(yaw, 0.0);
+    assert_close(pitch, 0.0);
+    assert_close(roll, 0.05);
+    assert_eq!(transform.translation, Vec3::ZERO);
+}
+
+#[test]
+fn shake_direction_orients_shake_2d() {
+    let mut app = testing::app();
+    let shake = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            Shake2d {
+                max_offset: Vec2::new(10.0, 10.0),
+                max_roll: 0.0,
+                trauma: 1.0,
+                trauma_power: 1.0,
+                decay: TraumaDecay::Linear(0.0),
+                random_sources: constant_sources(1.0),
+                ..default()
+            },
+            ShakeDirection {
+                anisotropy: 1.0,
+                direction: Vec3::X,
+            },
+        ))
+        .id();
+
+    app.update();
+
+    assert!(transform(&app, shake)
+        .translation
+        .abs_diff_eq(Vec3::new(10.0, 0.0, 0.0), 1e-5));
+}
+
+#[test]
+fn shake_direction_uses_the_rotation_on_the_spawn_frame() {
+    let mut app = testing::app();
+    let shake = app
+        .world_mut()
+        .spawn((
+            Transform::from_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2)),
+            Shake3d {
+                max_offset: Vec3::ONE,
+                max_yaw_pitch_roll: Vec3::ZERO,
+                trauma: 1.0,
+                trauma_power: 1.0,
+                decay: TraumaDecay::Linear(0.0),
+                random_sources: constant_sources(1.0),
+                mode: ShakeMode::Additive,
+            },
+            ShakeDirection {
+                anisotropy: 1.0,
+                direction: Vec3::X,
+            },
+        ))
+        .id();
+
+    app.update();
+
+    // Only moves along the world space direction, even though the camera is rotated.
+    assert!(transform(&app, shake)
+        .translation
+        .abs_diff_eq(Vec3::X, 1e-5));
+}
+
+#[test]
+fn shake_3d_rotates_in_yaw_pitch_roll() {
+    let mut app = testing::app();
+    let shake = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            Shake3d {
+                max_offset: Vec3::new(1.0, 2.0, 3.0),
+                max_yaw_pitch_roll: Vec3::new(0.1, 0.2, 0.3),
+                trauma: 1.0,
+                trauma_power: 1.0,
+                decay: TraumaDecay::Linear(0.0),
+                random_sources: constant_sources(0.5),
+                ..default()
+            },
+        ))
+        .id();
+
+    app.update();
+
+    let transform = transform(&app, shake);
+    let (yaw, pitch, roll) = transform.rotation.to_euler(EulerRot::YXZ);
+    assert_close(yaw, 0.05);
+    assert_close(pitch, 0.1);
+    assert_close(roll, 0.15);
+    assert!(transform
+        .translation
+        .abs_diff_eq(Vec3::new(0.5, 1.0, 1.5), 1e-5));
+}
+
+#[test]
+fn scripted_source_plays_back_values() {
+    use bevy_camera_shake::RandomSource;
+
+    let source = ScriptedSource::new([1.0, -1.0, 0.5], 0.1);
+    assert_eq!(source.rand(0.0), 1.0);
+    assert_eq!(source.rand(0.15), -1.0);
+    assert_eq!(source.rand(0.25), 0.5);
+    assert_eq!(source.rand(10.0), 0.5);
+}
+
+/// The number of shake entities whose `Transform` changed, counted at the end of every frame.
+#[derive(Resource, Default)]
+struct ChangedTransforms(usize);
+
+fn count_changed_transforms(
+    query: Query<(), (Changed<Transform>, With<Shake2d>)>,
+    mut changed: ResMut<ChangedTransforms>,
+) {
+    changed.0 += query.iter().count();
+}
+
+#[test]
+fn shakes_at_rest_do_not_change_transforms() {
+    let mut app = testing::app();
+    app.init_resource::<ChangedTransforms>()
+        .add_systems(Last, count_changed_transforms);
+    for mode in [ShakeMode::Replace, ShakeMode::Additive, ShakeMode::SubView] {
+        spawn_shake_2d(
+            &mut app,
+            Shake2d {
+                trauma: 0.0,
+                random_sources: constant_sources(1.0),
+                mode,
+                ..default()
+            },
+        );
+    }
+    let shaking = spawn_shake_2d(
+        &mut app,
+        Shake2d {
+            trauma: 1.0,
+            decay: TraumaDecay::Linear(0.0),
+            random_sources: [
+                ShakeSource::custom(|time: f32| time.sin()),
+                ScriptedSource::constant(0.0).into(),
+                ScriptedSource::constant(0.0).into(),
+            ],
+            ..default()
+        },
+    );
+
+    app.update();
+    app.world_mut().resource_mut::<ChangedTransforms>().0 = 0;
+    testing::run_frames(&mut app, 10);
+
+    // Only the shaking entity changed, once per frame.
+    assert_eq!(app.world().resource::<ChangedTransforms>().0, 10);
+    assert_ne!(transform(&app, shaking).translation, Vec3::ZERO);
+}
+
+/// Counts its samples, so that sampling mutates the shake.
+#[derive(Clone, Default)]
+struct CountingSampler(u32);
+
+impl ShakeSampler for CountingSampler {
+    fn sample(&mut self, _context: &SampleContext) -> f32 {
+        self.0 += 1;
+        (self.0 as f32).sin()
+    }
+}
+
+/// The number of shakes that changed, counted at the end of every frame.
+#[derive(Resource, Default)]
+struct ChangedShakes(usize);
+
+fn count_changed_shakes(query: Query<(), Changed<Shake2d>>, mut changed: ResMut<ChangedShakes>) {
+    changed.0 += query.iter().count();
+}
+
+#[test]
+fn decaying_shakes_are_not_marked_changed() {
+    let mut app = testing::app();
+    app.init_resource::<ChangedShakes>()
+        .add_systems(Last, count_changed_shakes);
+    spawn_shake_2d(
+        &mut app,
+        Shake2d {
+            trauma: 1.0,
+            decay: TraumaDecay::Linear(0.1),
+            random_sources: [
+                ShakeSource::sampler(CountingSampler::default()),
+                ScriptedSource::constant(0.0).into(),
+                ScriptedSource::constant(0.0).into(),
+            ],
+            ..default()
+        },
+    );
+
+    app.update();
+    app.world_mut().resource_mut::<ChangedShakes>().0 = 0;
+    testing::run_frames(&mut app, 10);
+
+    assert_eq!(app.world().resource::<ChangedShakes>().0, 0);
+}
+
+#[test]
+fn shakes_held_by_emitters_are_not_marked_changed() {
+    let mut app = testing::app();
+    app.init_resource::<ChangedShakes>()
+        .add_systems(Last, count_changed_shakes);
+    app.world_mut().spawn(ShakeEmitter::default());
+    let shake = spawn_shake_2d(
+        &mut app,
+        Shake2d {
+            // Keeps the shake at the emitter.
+            random_sources: constant_sources(0.0),
+            ..default()
+        },
+    );
+
+    app.update();
+    app.world_mut().resource_mut::<ChangedShakes>().0 = 0;
+    testing::run_frames(&mut app, 10);
+
+    assert_eq!(app.world().resource::<ChangedShakes>().0, 0);
+    // Held at the trauma of the emitter, minus one frame of decay.
+    assert_close(trauma_2d(&app, shake), 0.5 - 0.8 / 60.0);
+}
+
+#[test]
+fn inverse_square_falloff_without_min_distance_is_finite() {
+    let falloff = Falloff::InverseSquare { min_distance: 0.0 };
+    assert_eq!(falloff.multiplier(0.0, 10.0), 1.0);
+    assert!(falloff.multiplier(1.0, 10.0).is_finite());
+
+    let mut app = testing::app();
+    let shake = spawn_shake_2d(
+        &mut app,
+        Shake2d {
+            decay: TraumaDecay::Linear(0.0),
+            random_sources: constant_sources(0.0),
+            ..default()
+        },
+    );
+    app.update();
+
+    app.world_mut()
+        .trigger(ShakeImpulse::new(Vec3::ZERO, 0.5, 10.0).with_falloff(falloff));
+    assert_close(trauma_2d(&app, shake), 0.5);
+}
+
+/// Runs `frames` frames, and returns the `Transform` of the entity after each of them.
+fn record_transforms(app: &mut App, entity: Entity, frames: u32) -> Vec<Transform> {
+    (0..frames)
+        .map(|_| {
+            app.update();
+            transform(app, entity)
+        })
+        .collect()
+}
+
+/// Captures a snapshot of the shake, runs it, restores the snapshot and runs it again.
+fn assert_rollback_is_deterministic(shake: impl Bundle) {
+    let mut app = testing::app();
+    let entity = app
+        .world_mut()
+        .spawn((Transform::default(), ShakeClock::new(7), shake))
+        .id();
+    testing::run_frames(&mut app, 5);
+
+    let snapshot = ShakeSnapshot::capture(app.world().entity(entity)).unwrap();
+    let original = record_transforms(&mut app, entity, 30);
+    snapshot.restore(&mut app.world_mut().entity_mut(entity));
+    let resimulated = record_transforms(&mut app, entity, 30);
+
+    assert_eq!(original, resimulated);
+    assert!(original
+        .iter()
+        .any(|transform| transform.translation != Vec3::ZERO));
+}
+
+#[test]
+fn restored_snapshots_resimulate_the_same_shake() {
+    assert_rollback_is_deterministic(Shake2d {
+        trauma: 1.0,
+        random_sources: [0, 1, 2].map(ShakeSource::perlin),
+        ..default()
+    });
+}
+
+#[test]
+fn restored_snapshots_bring_back_finished_timed_shakes() {
+    assert_rollback_is_deterministic((
+        Shake2d {
+            trauma: 1.0,
+            decay: TraumaDecay::Linear(0.0),
+            random_sources: [0, 1, 2].map(ShakeSource::perlin),
+            ..default()
+        },
+        // Finishes after 12 frames, before the rollback.
+        TimedShake::new(0.05, 0.05, 0.5, 0.05, 0.05),
+    ));
+}
+
+#[test]
+fn timed_shakes_play_without_trauma() {
+    let mut app = testing::app();
+    let shake = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            Shake2d {
+                random_sources: constant_sources(1.0),
+                ..default()
+            },
+            TimedShake::new(3.0, 0.5, 0.8, 2.0, 4.0),
+        ))
+        .id();
+
+    // Halfway through the attack.
+    testing::run_frames(&mut app, 90);
+    assert_close(transform(&app, shake).translation.x, 50.0);
+}
+
+#[test]
+fn finished_timed_shakes_keep_the_trauma() {
+    let mut app = testing::app();
+    let shake = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            Shake2d {
+                trauma: 0.5,
+                decay: TraumaDecay::Linear(0.0),
+                random_sources: constant_sources(1.0),
+                ..default()
+            },
+            TimedShake::new(0.05, 0.05, 0.5, 0.05, 0.05),
+        ))
+        .id();
+
+    testing::run_frames(&mut app, 20);
+    assert!(app.world().get::<TimedShake>(shake).is_none());
+    assert_close(trauma_2d(&app, shake), 0.5);
+}
+
+/// Spawns a shake with two default layers, of which only the one at `active` has trauma.
+fn spawn_layered_shake(app: &mut App, active: usize) -> Entity {
+    let mut layers = ShakeLayers::new([ShakeLayer::new("a"), ShakeLayer::new("b")]);
+    layers.layers[active].trauma = 1.0;
+    app.world_mut()
+        .spawn((Transform::default(), Shake2d::default(), layers))
+        .id()
+}
+
+#[test]
+fn default_layers_are_not_correlated() {
+    let mut app = testing::app();
+    let first = spawn_layered_shake(&mut app, 0);
+    let second = spawn_layered_shake(&mut app, 1);
+
+    app.update();
+
+    assert_ne!(transform(&app, first), transform(&app, second));
+}
@@ -0,0 +1,219 @@
+use bevy::prelude::*;
+use bevy_camera_shake::{
+    testing, NoiseKind, PerlinNoise, RandomSource, SampleContext, Shake3d, ShakeNoise,
+    ShakeSampler, ShakeSource, TraumaDecay,
+};
+
+/// Returns a tenth of the axis index, scaled by the trauma.
+#[derive(Clone)]
+struct AxisSampler;
+
+impl ShakeSampler for AxisSampler {
+    fn sample(&mut self, context: &SampleContext) -> f32 {
+        context.axis as f32 * 0.1 * context.trauma
+    }
+}
+
+/// Alternates between `1.0` and `-1.0` on every sample.
+#[derive(Clone, Default)]
+struct AlternatingSampler {
+    samples: u32,
+}
+
+impl ShakeSampler for AlternatingSampler {
+    fn sample(&mut self, _context: &SampleContext) -> f32 {
+        self.samples += 1;
+        if self.samples % 2 == 1 {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+}
+
+fn spawn_shake_3d(app: &mut App, random_sources: [ShakeSource; 6]) -> Entity {
+    app.world_mut()
+        .spawn((
+            Transform::default(),
+            Shake3d {
+                max_offset: Vec3::ONE,
+                max_yaw_pitch_roll: Vec3::ZERO,
+                trauma: 0.5,
+                trauma_power: 1.0,
+                decay: TraumaDecay::Linear(0.0),
+                random_sources,
+                ..default()
+            },
+        ))
+        .id()
+}
+
+#[test]
+fn closures_are_random_sources() {
+    let source = ShakeSource::custom(|time: f32| time * 0.5);
+    assert_eq!(source.rand(1.0), 0.5);
+    assert_eq!(source.rand(-1.0), -0.5);
+}
+
+#[cfg(feature = "noise")]
+#[test]
+fn noise_fn_source_samples_along_time() {
+    use bevy::math::Vec2;
+    use bevy_camera_shake::NoiseFnSource;
+    use noise::{Fbm, Perlin};
+
+    let source = NoiseFnSource::new(Fbm::<Perlin>::new(0))
+        .with_frequency(2.0)
+        .with_offset(Vec2::new(0.3, 7.0));
+    let noise = noise::NoiseFn::<f64, 2>::get(&source.noise, [0.3 + 2.0 * 0.25, 7.0]) as f32;
+    let expected = noise.clamp(-1.0, 1.0);
+    assert!((source.rand(0.25) - expected).abs() < 1e-6);
+    for i in 0..100 {
+        assert!((-1.0..=1.0).contains(&source.rand(i as f32 * 0.37)));
+    }
+}
+
+#[test]
+fn samplers_receive_the_axis_and_trauma() {
+    let mut app = testing::app();
+    let shake = spawn_shake_3d(
+        &mut app,
+        std::array::from_fn(|_| ShakeSource::sampler(AxisSampler)),
+    );
+
+    app.update();
+
+    let translation = app.world().get::<Transform>(shake).unwrap().translation;
+    // The trauma of 0.5 scales both the sample and the shake.
+    assert!(translation.abs_diff_eq(Vec3::new(0.0, 0.025, 0.05), 1e-6));
+}
+
+#[test]
+fn samplers_keep_their_state() {
+    let mut app = testing::app();
+    let shake = spawn_shake_3d(
+        &mut app,
+        std::array::from_fn(|_| ShakeSource::sampler(AlternatingSampler::default())),
+    );
+
+    app.update();
+    let first = app.world().get::<Transform>(shake).unwrap().translation;
+    app.update();
+    let second = app.world().get::<Transform>(shake).unwrap().translation;
+
+    assert_eq!(first, Vec3::splat(0.5));
+    assert_eq!(second, Vec3::splat(-0.5));
+}
+
+#[test]
+fn cloned_samplers_have_their_own_state() {
+    let mut source = ShakeSource::sampler(AlternatingSampler::default());
+    let context = SampleContext::at_time(0.0);
+    assert_eq!(source.sample(&context), 1.0);
+
+    let mut clone = source.clone();
+    assert_eq!(source.sample(&context), -1.0);
+    assert_eq!(clone.sample(&context), -1.0);
+    assert_eq!(clone.sample(&context), 1.0);
+    assert_eq!(source.sample(&context), 1.0);
+}
+
+#[test]
+fn random_sources_are_sampled_with_the_context_time() {
+    let mut source = ShakeSource::custom(|time: f32| time);
+    let context = SampleContext {
+        axis: 3,
+        delta: 0.1,
+        trauma: 0.5,
+        ..SampleContext::at_time(0.25)
+    };
+    assert_eq!(source.sample(&context), 0.25);
+}
+
+#[test]
+fn shake_noise_channels_are_decorrelated() {
+    for kind in [NoiseKind::Perlin, NoiseKind::OpenSimplex, NoiseKind::Value] {
+        let noise = ShakeNoise::new(kind, 7);
+        let mut differences = [0.0; 5];
+        for i in 0..200 {
+            let mut channels = [0.0; 6];
+            noise.sample(i as f32 * 0.013, &mut channels);
+            for (difference, pair) in differences.iter_mut().zip(channels.windows(2)) {
+                assert!((-1.0..=1.0).contains(&pair[0]));
+                *difference += (pair[0] - pair[1]).abs();
+            }
+        }
+        for difference in differences {
+            assert!(difference > 1.0, "{kind:?} channels move in lockstep");
+        }
+    }
+}
+
+#[test]
+fn shake_noise_first_channel_matches_single_noise() {
+    let noise = ShakeNoise::new(NoiseKind::Perlin, 3);
+    let mut channels = [0.0; 3];
+    noise.sample(0.37, &mut channels);
+    assert_eq!(channels[0], PerlinNoise::new(3).rand(0.37));
+}
+
+#[test]
+fn shake_noise_replaces_random_sources() {
+    let mut app = testing::app();
+    let shake = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            Shake3d {
+                max_offset: Vec3::ONE,
+                trauma: 1.0,
+                decay: TraumaDecay::Linear(0.0),
+                ..default()
+            },
+            ShakeNoise::new(NoiseKind::Perlin, 3),
+        ))
+        .id();
+
+    testing::run_frames(&mut app, 2);
+
+    let translation = app.world().get::<Transform>(shake).unwrap().translation;
+    // The default random sources always return 0.5, which would move every axis the same way.
+    assert_ne!(translation, Vec3::splat(0.5));
+    assert!(translation.x != translation.y && translation.y != translation.z);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn default_shake_round_trips_through_serde() {
+    use bevy::math::curve::{FunctionCurve, Interval};
+    let shake = Shake3d {
+        decay: TraumaDecay::Curve(std::sync::Arc::new(FunctionCurve::new(
+            Interval::UNIT,
+            |trauma| trauma,
+        ))),
+        random_sources: [
+            ShakeSource::perlin(1),
+            ShakeSource::custom(|time: f32| time),
+            ShakeSource::sampler(AxisSampler),
+            default(),
+            default(),
+            default(),
+        ],
+        ..default()
+    };
+
+    let serialized = ron::to_string(&Shake3d::default()).unwrap();
+    let deserialized: Shake3d = ron::from_str(&serialized).unwrap();
+    assert_eq!(ron::to_string(&deserialized).unwrap(), serialized);
+
+    let serialized = ron::to_string(&shake).unwrap();
+    let deserialized: Shake3d = ron::from_str(&serialized).unwrap();
+    assert_eq!(
+        deserialized.random_sources[0].rand(0.37),
+        shake.random_sources[0].rand(0.37)
+    );
+    // Sources and curves that can't be serialized come back as the defaults.
+    assert_eq!(deserialized.random_sources[1].rand(0.37), 0.5);
+    assert_eq!(deserialized.random_sources[2].rand(0.37), 0.5);
+    assert_eq!(deserialized.decay.decay(0.5, 1.0), 0.5);
+}
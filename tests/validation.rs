@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use bevy_camera_shake::{
+    testing::{self, ScriptedSource},
+    Shake2d, Shake3d, ShakeProblem, ShakeProblemReported, ShakeSource,
+};
+
+#[test]
+fn default_sources_are_reported() {
+    let problems = Shake3d::default().validate();
+    let missing: Vec<_> = (0..6)
+        .map(|axis| ShakeProblem::MissingRandomSource { axis })
+        .collect();
+    assert_eq!(problems, missing);
+}
+
+#[test]
+fn configured_shake_has_no_problems() {
+    let shake = Shake2d {
+        random_sources: [
+            ShakeSource::perlin(0),
+            ShakeSource::open_simplex(1),
+            ScriptedSource::constant(1.0).into(),
+        ],
+        ..default()
+    };
+    assert_eq!(shake.validate(), Vec::new());
+}
+
+#[test]
+fn invalid_parameters_are_reported() {
+    let shake = Shake2d {
+        max_offset: Vec2::new(-1.0, 1.0),
+        max_roll: f32::NAN,
+        trauma: 1.5,
+        trauma_power: 0.0,
+        random_sources: [0, 1, 2].map(ShakeSource::perlin),
+        ..default()
+    };
+    let problems = shake.validate();
+
+    assert_eq!(problems.len(), 4);
+    assert!(problems.contains(&ShakeProblem::InvalidParameter {
+        name: "max_offset.x",
+        value: -1.0
+    }));
+    assert!(problems.iter().any(|problem| matches!(
+        problem,
+        ShakeProblem::InvalidParameter {
+            name: "max_roll",
+            ..
+        }
+    )));
+    assert!(problems.contains(&ShakeProblem::TraumaAboveOne(1.5)));
+    assert!(problems.contains(&ShakeProblem::InvalidParameter {
+        name: "trauma_power",
+        value: 0.0
+    }));
+}
+
+/// Every problem reported so far.
+#[derive(Resource, Default)]
+struct Reported(Vec<ShakeProblem>);
+
+fn collect_reported(mut events: EventReader<ShakeProblemReported>, mut reported: ResMut<Reported>) {
+    reported
+        .0
+        .extend(events.read().map(|event| event.problem.clone()));
+}
+
+#[test]
+fn problems_are_reported_once_until_fixed() {
+    let mut app = testing::app();
+    app.init_resource::<Reported>()
+        .add_systems(Last, collect_reported);
+    let shake = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            Shake2d {
+                max_offset: Vec2::new(-1.0, 1.0),
+                trauma: 1.0,
+                random_sources: [0, 1, 2].map(ShakeSource::perlin),
+                ..default()
+            },
+        ))
+        .id();
+    let problem = ShakeProblem::InvalidParameter {
+        name: "max_offset.x",
+        value: -1.0,
+    };
+
+    testing::run_frames(&mut app, 10);
+    assert_eq!(app.world().resource::<Reported>().0, vec![problem.clone()]);
+
+    let mut shake_2d = app.world_mut().get_mut::<Shake2d>(shake).unwrap();
+    shake_2d.max_offset.x = 1.0;
+    testing::run_frames(&mut app, 10);
+    assert_eq!(app.world().resource::<Reported>().0.len(), 1);
+
+    let mut shake_2d = app.world_mut().get_mut::<Shake2d>(shake).unwrap();
+    shake_2d.max_offset.x = -1.0;
+    testing::run_frames(&mut app, 10);
+    assert_eq!(
+        app.world().resource::<Reported>().0,
+        vec![problem.clone(), problem]
+    );
+}